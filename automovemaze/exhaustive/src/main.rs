@@ -0,0 +1,33 @@
+use lib::automovemaze::{Agent, ExhaustiveSolver, MovementRule, State};
+
+fn main() {
+    let seed = rng::seed_from_args();
+    let initial_state = State::new(rng::board_seed(seed), 5, 5, 3, 5, MovementRule::default());
+    let (placement, final_state) = ExhaustiveSolver::new(true).play_game(&initial_state);
+
+    println!("seed:\t{}", seed);
+    println!("{:?}", placement);
+    println!("{:?}", final_state);
+}
+
+#[cfg(test)]
+mod tests {
+    use harness::Harness;
+    use lib::automovemaze::Agent;
+    use lib::automovemaze::{ExhaustiveSolver, MovementRule, State};
+
+    #[test]
+    fn score() {
+        let mut harness = Harness::new((0..100).collect());
+        harness.run("exhaustive", |seed| {
+            let initial_state =
+                State::new(rng::board_seed(seed), 5, 5, 3, 5, MovementRule::default());
+            let (_, last_state) = ExhaustiveSolver::new(true).play_game(&initial_state);
+
+            last_state.score()
+        });
+
+        println!("{}", harness);
+        assert_eq!(harness.results()[0].total_score(), 9563);
+    }
+}
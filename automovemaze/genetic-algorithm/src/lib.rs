@@ -0,0 +1,187 @@
+use std::time::{Duration, Instant};
+
+use lib::automovemaze::Agent;
+use lib::automovemaze::{Evaluator, Placement, State};
+use rng::Pcg32;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeneticAlgorithmParams {
+    pub population_size: usize,
+    pub max_generations: u32,
+    // Stops early once this much wall-clock time has passed
+    pub time_limit: Option<Duration>,
+    pub tournament_size: usize,
+    pub mutation_rate: f64,
+    pub num_elites: usize,
+}
+
+pub struct GeneticAlgorithmAgent {
+    rng: Pcg32,
+    params: GeneticAlgorithmParams,
+}
+
+impl GeneticAlgorithmAgent {
+    pub fn new(
+        seed: u64,
+        population_size: usize,
+        max_generations: u32,
+        time_limit: Option<Duration>,
+        tournament_size: usize,
+        mutation_rate: f64,
+        num_elites: usize,
+    ) -> Self {
+        Self::with_params(
+            seed,
+            GeneticAlgorithmParams {
+                population_size,
+                max_generations,
+                time_limit,
+                tournament_size,
+                mutation_rate,
+                num_elites,
+            },
+        )
+    }
+
+    pub fn with_params(seed: u64, params: GeneticAlgorithmParams) -> Self {
+        assert!(params.population_size > 0, "the population needs an individual");
+        assert!(params.tournament_size > 0, "a tournament needs an entrant");
+
+        GeneticAlgorithmAgent {
+            rng: Pcg32::new(seed),
+            params,
+        }
+    }
+
+    pub fn params(&self) -> &GeneticAlgorithmParams {
+        &self.params
+    }
+
+    // The best of tournament_size individuals drawn at random
+    fn select<'p>(&mut self, population: &'p [(Placement, i32)]) -> &'p Placement {
+        let (placement, _) = (0..self.params.tournament_size)
+            .map(|_| &population[self.rng.gen_range(0..population.len())])
+            .max_by_key(|(_, score)| *score)
+            .unwrap();
+
+        placement
+    }
+
+    // Each character's coordinate is taken from either parent
+    fn crossover(&mut self, a: &Placement, b: &Placement) -> Placement {
+        let coords = a
+            .coords()
+            .iter()
+            .zip(b.coords())
+            .map(|(&a, &b)| if self.rng.gen_bool(0.5) { a } else { b })
+            .collect::<Vec<_>>();
+
+        Placement::new(coords)
+    }
+
+    // Each character is moved to a random cell with probability mutation_rate
+    fn mutate(&mut self, state: &State, placement: &mut Placement) {
+        for i in 0..placement.len() {
+            if self.rng.gen_bool(self.params.mutation_rate) {
+                placement.set_coord(
+                    i,
+                    self.rng.gen_range(0..state.h()),
+                    self.rng.gen_range(0..state.w()),
+                );
+            }
+        }
+    }
+}
+
+impl Agent for GeneticAlgorithmAgent {
+    fn play_game(&mut self, state: &State) -> (Placement, State) {
+        let started_at = Instant::now();
+        let mut evaluator = Evaluator::new(state);
+        let mut population = (0..self.params.population_size)
+            .map(|_| {
+                let placement = Placement::random(&mut self.rng, state);
+                let score = evaluator.evaluate(&placement);
+
+                (placement, score)
+            })
+            .collect::<Vec<_>>();
+
+        // Stop at whichever of the generation and time limits comes first
+        for _ in 0..self.params.max_generations {
+            if self
+                .params
+                .time_limit
+                .is_some_and(|time_limit| time_limit <= started_at.elapsed())
+            {
+                break;
+            }
+
+            population.sort_by_key(|(_, score)| -score);
+
+            let mut next_population =
+                population[..self.params.num_elites.min(population.len())].to_vec();
+
+            while next_population.len() < self.params.population_size {
+                let a = self.select(&population);
+                let b = self.select(&population);
+                let mut child = self.crossover(a, b);
+                self.mutate(state, &mut child);
+                let score = evaluator.evaluate(&child);
+
+                next_population.push((child, score));
+            }
+
+            population = next_population;
+        }
+
+        let (best_placement, _) = population
+            .into_iter()
+            .max_by_key(|(_, score)| *score)
+            .unwrap();
+        let final_state = state.simulate(&best_placement);
+
+        (best_placement, final_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use harness::Harness;
+    use lib::automovemaze::Agent;
+    use lib::automovemaze::{MovementRule, State};
+    use rng::Pcg32;
+
+    use super::GeneticAlgorithmAgent;
+
+    #[test]
+    fn score() {
+        let mut harness = Harness::new((0..100).collect());
+        harness.run("genetic-algorithm", |seed| {
+            let mut rng = Pcg32::new(seed);
+            let initial_state = State::new(rng.next_u64(), 5, 5, 3, 5, MovementRule::default());
+            let (_, last_state) =
+                GeneticAlgorithmAgent::new(rng.next_u64(), 50, 200, None, 3, 0.2, 2)
+                    .play_game(&initial_state);
+
+            last_state.score()
+        });
+
+        println!("{}", harness);
+        assert_eq!(harness.results()[0].total_score(), 9552);
+    }
+
+    #[test]
+    fn time_limit() {
+        let mut rng = Pcg32::new(0);
+        let initial_state = State::new(rng.next_u64(), 5, 5, 3, 5, MovementRule::default());
+        let time_limit = Some(Duration::from_millis(50));
+        let (placement, last_state) =
+            GeneticAlgorithmAgent::new(rng.next_u64(), 50, u32::MAX, time_limit, 3, 0.2, 2)
+                .play_game(&initial_state);
+
+        assert_eq!(initial_state.evaluate(&placement), last_state.score());
+    }
+}
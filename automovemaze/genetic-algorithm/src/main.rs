@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+use genetic_algorithm::GeneticAlgorithmAgent;
+use lib::automovemaze::{Agent, MovementRule, State};
+
+fn main() {
+    let seed = rng::seed_from_args();
+    let initial_state = State::new(rng::board_seed(seed), 5, 5, 3, 5, MovementRule::default());
+    let (placement, final_state) = GeneticAlgorithmAgent::new(
+        rng::agent_seed(seed, 0),
+        50,
+        200,
+        Some(Duration::from_secs(1)),
+        3,
+        0.2,
+        2,
+    )
+    .play_game(&initial_state);
+
+    println!("seed:\t{}", seed);
+    println!("{:?}", placement);
+    println!("{:?}", final_state);
+}
@@ -0,0 +1,107 @@
+use lib::automovemaze::Agent;
+use lib::automovemaze::{Evaluator, Placement, State};
+use rng::Pcg32;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GreatDelugeParams {
+    pub count: u32,
+    pub rain_speed: f64,
+}
+
+pub struct GreatDelugeAgent {
+    rng: Pcg32,
+    params: GreatDelugeParams,
+}
+
+impl GreatDelugeAgent {
+    pub fn new(seed: u64, count: u32, rain_speed: f64) -> Self {
+        Self::with_params(seed, GreatDelugeParams { count, rain_speed })
+    }
+
+    pub fn with_params(seed: u64, params: GreatDelugeParams) -> Self {
+        GreatDelugeAgent {
+            rng: Pcg32::new(seed),
+            params,
+        }
+    }
+
+    pub fn params(&self) -> &GreatDelugeParams {
+        &self.params
+    }
+}
+
+impl Agent for GreatDelugeAgent {
+    fn play_game(&mut self, state: &State) -> (Placement, State) {
+        let mut evaluator = Evaluator::new(state);
+        let mut placement = state.placement();
+        let mut score = evaluator.evaluate(&placement);
+        let mut best_placement = placement.clone();
+        let mut best_score = score;
+
+        // Starts at the initial score and rises by rain_speed every iteration
+        let mut water_level = score as f64;
+
+        for _ in 0..self.params.count {
+            let neighbor = placement.random_neighbor(&mut self.rng, state);
+            let neighbor_score = evaluator.evaluate(&neighbor);
+
+            // Accept any improvement, or anything still above the water
+            if neighbor_score >= score || neighbor_score as f64 >= water_level {
+                placement = neighbor;
+                score = neighbor_score;
+
+                if best_score < score {
+                    best_placement = placement.clone();
+                    best_score = score;
+                }
+            }
+
+            water_level += self.params.rain_speed;
+        }
+
+        let final_state = state.simulate(&best_placement);
+
+        (best_placement, final_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use harness::Harness;
+    use lib::automovemaze::Agent;
+    use lib::automovemaze::{MovementRule, State};
+    use rng::Pcg32;
+
+    use super::GreatDelugeAgent;
+
+    #[test]
+    fn score() {
+        let mut harness = Harness::new((0..100).collect());
+        harness.run("great-deluge", |seed| {
+            let mut rng = Pcg32::new(seed);
+            let initial_state = State::new(rng.next_u64(), 5, 5, 3, 5, MovementRule::default());
+            let (_, last_state) =
+                GreatDelugeAgent::new(rng.next_u64(), 10000, 0.01).play_game(&initial_state);
+
+            last_state.score()
+        });
+
+        println!("{}", harness);
+        assert_eq!(harness.results()[0].total_score(), 9553);
+    }
+
+    #[test]
+    fn never_worse_than_initial_placement() {
+        for seed in 0..100 {
+            let mut rng = Pcg32::new(seed);
+            let initial_state = State::new(rng.next_u64(), 5, 5, 3, 5, MovementRule::default());
+            let initial_score = initial_state.evaluate(&initial_state.placement());
+            let (placement, last_state) =
+                GreatDelugeAgent::new(rng.next_u64(), 100, 0.01).play_game(&initial_state);
+
+            assert!(initial_score <= last_state.score());
+            assert_eq!(initial_state.evaluate(&placement), last_state.score());
+        }
+    }
+}
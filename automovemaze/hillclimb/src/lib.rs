@@ -0,0 +1,79 @@
+use lib::automovemaze::Agent;
+use lib::automovemaze::{Evaluator, Placement, State};
+use rng::Pcg32;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HillClimbParams {
+    pub count: u32,
+}
+
+pub struct HillClimbAgent {
+    rng: Pcg32,
+    params: HillClimbParams,
+}
+
+impl HillClimbAgent {
+    pub fn new(seed: u64, count: u32) -> Self {
+        Self::with_params(seed, HillClimbParams { count })
+    }
+
+    pub fn with_params(seed: u64, params: HillClimbParams) -> Self {
+        HillClimbAgent {
+            rng: Pcg32::new(seed),
+            params,
+        }
+    }
+
+    pub fn params(&self) -> &HillClimbParams {
+        &self.params
+    }
+}
+
+impl Agent for HillClimbAgent {
+    fn play_game(&mut self, state: &State) -> (Placement, State) {
+        let mut evaluator = Evaluator::new(state);
+        let mut placement = state.placement();
+        let mut score = evaluator.evaluate(&placement);
+
+        for _ in 0..self.params.count {
+            let neighbor = placement.random_neighbor(&mut self.rng, state);
+            let neighbor_score = evaluator.evaluate(&neighbor);
+
+            if neighbor_score > score {
+                placement = neighbor;
+                score = neighbor_score;
+            }
+        }
+
+        let final_state = state.simulate(&placement);
+
+        (placement, final_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use harness::Harness;
+    use lib::automovemaze::Agent;
+    use lib::automovemaze::{MovementRule, State};
+    use rng::Pcg32;
+
+    use super::HillClimbAgent;
+
+    #[test]
+    fn score() {
+        let mut harness = Harness::new((0..100).collect());
+        harness.run("hillclimb", |seed| {
+            let mut rng = Pcg32::new(seed);
+            let initial_state = State::new(rng.next_u64(), 5, 5, 3, 5, MovementRule::default());
+            let (_, last_state) =
+                HillClimbAgent::new(rng.next_u64(), 10000).play_game(&initial_state);
+
+            last_state.score()
+        });
+
+        println!("{}", harness);
+        assert_eq!(harness.results()[0].total_score(), 9399);
+    }
+}
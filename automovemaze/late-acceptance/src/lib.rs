@@ -0,0 +1,115 @@
+use lib::automovemaze::Agent;
+use lib::automovemaze::{Evaluator, Placement, State};
+use rng::Pcg32;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LateAcceptanceParams {
+    pub count: u32,
+    pub history_length: usize,
+}
+
+pub struct LateAcceptanceAgent {
+    rng: Pcg32,
+    params: LateAcceptanceParams,
+}
+
+impl LateAcceptanceAgent {
+    pub fn new(seed: u64, count: u32, history_length: usize) -> Self {
+        Self::with_params(
+            seed,
+            LateAcceptanceParams {
+                count,
+                history_length,
+            },
+        )
+    }
+
+    pub fn with_params(seed: u64, params: LateAcceptanceParams) -> Self {
+        LateAcceptanceAgent {
+            rng: Pcg32::new(seed),
+            params,
+        }
+    }
+
+    pub fn params(&self) -> &LateAcceptanceParams {
+        &self.params
+    }
+}
+
+impl Agent for LateAcceptanceAgent {
+    fn play_game(&mut self, state: &State) -> (Placement, State) {
+        let mut evaluator = Evaluator::new(state);
+        let mut placement = state.placement();
+        let mut score = evaluator.evaluate(&placement);
+        let mut best_placement = placement.clone();
+        let mut best_score = score;
+
+        // Scores of the current placement in the last history_length iterations
+        let mut history = vec![score; self.params.history_length.max(1)];
+
+        for i in 0..self.params.count as usize {
+            let neighbor = placement.random_neighbor(&mut self.rng, state);
+            let neighbor_score = evaluator.evaluate(&neighbor);
+            let v = i % history.len();
+
+            // Accept anything no worse than the current placement or than the
+            // current placement history_length iterations ago
+            if neighbor_score >= score || neighbor_score >= history[v] {
+                placement = neighbor;
+                score = neighbor_score;
+
+                if best_score < score {
+                    best_placement = placement.clone();
+                    best_score = score;
+                }
+            }
+
+            history[v] = score;
+        }
+
+        let final_state = state.simulate(&best_placement);
+
+        (best_placement, final_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use harness::Harness;
+    use lib::automovemaze::Agent;
+    use lib::automovemaze::{MovementRule, State};
+    use rng::Pcg32;
+
+    use super::LateAcceptanceAgent;
+
+    #[test]
+    fn score() {
+        let mut harness = Harness::new((0..100).collect());
+        harness.run("late-acceptance", |seed| {
+            let mut rng = Pcg32::new(seed);
+            let initial_state = State::new(rng.next_u64(), 5, 5, 3, 5, MovementRule::default());
+            let (_, last_state) =
+                LateAcceptanceAgent::new(rng.next_u64(), 10000, 50).play_game(&initial_state);
+
+            last_state.score()
+        });
+
+        println!("{}", harness);
+        assert_eq!(harness.results()[0].total_score(), 9551);
+    }
+
+    #[test]
+    fn never_worse_than_initial_placement() {
+        for seed in 0..100 {
+            let mut rng = Pcg32::new(seed);
+            let initial_state = State::new(rng.next_u64(), 5, 5, 3, 5, MovementRule::default());
+            let initial_score = initial_state.evaluate(&initial_state.placement());
+            let (placement, last_state) =
+                LateAcceptanceAgent::new(rng.next_u64(), 100, 10).play_game(&initial_state);
+
+            assert!(initial_score <= last_state.score());
+            assert_eq!(initial_state.evaluate(&placement), last_state.score());
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use late_acceptance::LateAcceptanceAgent;
+use lib::automovemaze::{Agent, MovementRule, State};
+
+fn main() {
+    let seed = rng::seed_from_args();
+    let initial_state = State::new(rng::board_seed(seed), 5, 5, 3, 5, MovementRule::default());
+    let (placement, final_state) =
+        LateAcceptanceAgent::new(rng::agent_seed(seed, 0), 10000, 50).play_game(&initial_state);
+
+    println!("seed:\t{}", seed);
+    println!("{:?}", placement);
+    println!("{:?}", final_state);
+}
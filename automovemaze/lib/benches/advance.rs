@@ -0,0 +1,57 @@
+use std::hint::black_box;
+
+use automovemaze_lib::automovemaze::{MovementRule, Placement, State};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use rng::Pcg32;
+
+// One turn of every character moving, from a random placement
+fn advance(c: &mut Criterion) {
+    let mut group = c.benchmark_group("automovemaze/advance");
+
+    for (size, num_characters) in [(5, 3), (50, 3), (50, 30)] {
+        let mut placed = State::new(0, size, size, num_characters, 10, MovementRule::default());
+        let placement = Placement::random(&mut Pcg32::new(0), &placed);
+        for (i, &(y, x)) in placement.coords().iter().enumerate() {
+            placed.set_character_coord(i, x, y);
+        }
+        let label = format!("{}x{}/{}", size, size, num_characters);
+
+        group.bench_with_input(BenchmarkId::new("State", &label), &placed, |b, placed| {
+            b.iter_batched(
+                || placed.clone(),
+                |mut state| {
+                    state.advance();
+                    state
+                },
+                BatchSize::SmallInput,
+            );
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("valid_actions", &label),
+            &placed,
+            |b, placed| b.iter(|| black_box(placed).valid_actions(0)),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("legal_actions_into", &label),
+            &placed,
+            |b, placed| {
+                let mut actions = Vec::with_capacity(9);
+                b.iter(|| {
+                    black_box(placed).legal_actions_into(0, &mut actions);
+                    actions.len()
+                })
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("clone", &label), &placed, |b, placed| {
+            b.iter(|| black_box(placed).clone());
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, advance);
+criterion_main!(benches);
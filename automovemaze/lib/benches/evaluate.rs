@@ -0,0 +1,35 @@
+use automovemaze_lib::automovemaze::{Evaluator, MovementRule, Placement, State};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rng::Pcg32;
+
+// Evaluations per second of a random placement, with and without reusing the
+// board buffer between evaluations
+fn evaluate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("automovemaze/evaluate");
+    group.throughput(Throughput::Elements(1));
+
+    for (size, max_turn) in [(5, 5), (50, 50)] {
+        let state = State::new(0, size, size, 3, max_turn, MovementRule::default());
+        let mut rng = Pcg32::new(0);
+        let placements = (0..64)
+            .map(|_| Placement::random(&mut rng, &state))
+            .collect::<Vec<_>>();
+        let label = format!("{}x{}", size, size);
+
+        group.bench_with_input(BenchmarkId::new("State", &label), &state, |b, state| {
+            let mut placements = placements.iter().cycle();
+            b.iter(|| state.evaluate(placements.next().unwrap()));
+        });
+
+        group.bench_with_input(BenchmarkId::new("Evaluator", &label), &state, |b, state| {
+            let mut evaluator = Evaluator::new(state);
+            let mut placements = placements.iter().cycle();
+            b.iter(|| evaluator.evaluate(placements.next().unwrap()));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, evaluate);
+criterion_main!(benches);
@@ -1,27 +1,213 @@
 pub mod automovemaze {
-    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rng::Pcg32;
     use std::fmt::Debug;
+    use std::str::FromStr;
 
     pub trait Agent {
-        // Play a game and return the final state
-        fn play_game(&mut self, state: &State) -> State;
+        // Play a game and return the chosen placement and the final state
+        fn play_game(&mut self, state: &State) -> (Placement, State);
     }
 
     #[derive(Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct Character {
         y: usize,
         x: usize,
     }
 
-    #[derive(Clone, Copy)]
+    // Initial coordinates (y, x) of each character
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Placement {
+        coords: Vec<(usize, usize)>,
+    }
+
+    impl Placement {
+        pub fn new(coords: Vec<(usize, usize)>) -> Self {
+            Placement { coords }
+        }
+
+        pub fn random(rng: &mut Pcg32, state: &State) -> Self {
+            let coords = (0..state.num_characters())
+                .map(|_| (rng.gen_range(0..state.h()), rng.gen_range(0..state.w())))
+                .collect::<Vec<_>>();
+
+            Placement { coords }
+        }
+
+        // A copy with one random character moved to a random cell, which may
+        // be the cell it is already on
+        pub fn random_neighbor(&self, rng: &mut Pcg32, state: &State) -> Self {
+            let mut neighbor = self.clone();
+            neighbor.set_coord(
+                rng.gen_range(0..state.num_characters()),
+                rng.gen_range(0..state.h()),
+                rng.gen_range(0..state.w()),
+            );
+
+            neighbor
+        }
+
+        pub fn len(&self) -> usize {
+            self.coords.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.coords.is_empty()
+        }
+
+        pub fn coords(&self) -> &[(usize, usize)] {
+            self.coords.as_ref()
+        }
+
+        pub fn coord(&self, i: usize) -> (usize, usize) {
+            self.coords[i]
+        }
+
+        pub fn set_coord(&mut self, i: usize, y: usize, x: usize) {
+            self.coords[i] = (y, x);
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(rename_all = "snake_case")
+    )]
     pub enum Action {
         Up,
         Down,
         Left,
         Right,
+        UpLeft,
+        UpRight,
+        DownLeft,
+        DownRight,
+        Stay,
+    }
+
+    // Every action in the default comparison order
+    const ALL_ACTIONS: [Action; 9] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::UpLeft,
+        Action::UpRight,
+        Action::DownLeft,
+        Action::DownRight,
+        Action::Stay,
+    ];
+
+    impl Action {
+        fn delta(self) -> (i32, i32) {
+            match self {
+                Action::Up => (-1, 0),
+                Action::Down => (1, 0),
+                Action::Left => (0, -1),
+                Action::Right => (0, 1),
+                Action::UpLeft => (-1, -1),
+                Action::UpRight => (-1, 1),
+                Action::DownLeft => (1, -1),
+                Action::DownRight => (1, 1),
+                Action::Stay => (0, 0),
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(rename_all = "snake_case")
+    )]
+    pub enum TieBreak {
+        // The last of the best moves in Up/Down/Left/Right/diagonals/Stay order
+        Last,
+        // The first of the best moves in the given order; moves missing from it
+        // are tried after the listed ones
+        Priority(Vec<Action>),
+    }
+
+    // How a character picks its next cell: always the reachable cell with the
+    // most points, with the candidate moves and tie-break given here
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct MovementRule {
+        pub eight_directions: bool,
+        pub allow_stay: bool,
+        pub wrap_around: bool,
+        pub tie_break: TieBreak,
+    }
+
+    impl MovementRule {
+        // Candidate moves in the order they are compared
+        pub fn actions(&self) -> Vec<Action> {
+            let mut actions = Vec::with_capacity(9);
+            self.for_each_action(|action| actions.push(action));
+
+            actions
+        }
+
+        // Calls f on each candidate move in order, without allocating. A
+        // priority order lists its enabled moves first, each at its first
+        // position, followed by the others in the default order.
+        fn for_each_action<F: FnMut(Action)>(&self, mut f: F) {
+            let enabled = |action: Action| match action {
+                Action::Up | Action::Down | Action::Left | Action::Right => true,
+                Action::UpLeft | Action::UpRight | Action::DownLeft | Action::DownRight => {
+                    self.eight_directions
+                }
+                Action::Stay => self.allow_stay,
+            };
+            let order: &[Action] = match &self.tie_break {
+                TieBreak::Last => &[],
+                TieBreak::Priority(order) => order,
+            };
+
+            for (i, &action) in order.iter().enumerate() {
+                if enabled(action) && !order[..i].contains(&action) {
+                    f(action);
+                }
+            }
+            for action in ALL_ACTIONS {
+                if enabled(action) && !order.contains(&action) {
+                    f(action);
+                }
+            }
+        }
+
+        // Whether a candidate worth `point` replaces the best one found so far
+        fn prefers(&self, point: i32, best_point: i32) -> bool {
+            match self.tie_break {
+                TieBreak::Last => point >= best_point,
+                TieBreak::Priority(_) => point > best_point,
+            }
+        }
+    }
+
+    impl Default for MovementRule {
+        fn default() -> Self {
+            MovementRule {
+                eight_directions: false,
+                allow_stay: false,
+                wrap_around: false,
+                tie_break: TieBreak::Last,
+            }
+        }
     }
 
+    // With the serde feature a state is stored without its dimensions, which
+    // follow from the points, e.g.
+    //   {"max_turn":4,"turn":0,"score":0,"points":[[1,2],[3,4]],
+    //    "characters":[{"y":0,"x":1}],"rule":{"eight_directions":false,...}}
     #[derive(Clone)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(into = "StateData", try_from = "StateData")
+    )]
     pub struct State {
         h: usize,
         w: usize,
@@ -30,6 +216,7 @@ pub mod automovemaze {
         score: i32,
         points: Vec<Vec<i32>>,
         characters: Vec<Character>,
+        rule: MovementRule,
     }
 
     impl State {
@@ -39,16 +226,21 @@ pub mod automovemaze {
             w: usize,
             num_characters: usize,
             max_turn: i32,
+            rule: MovementRule,
         ) -> Self {
-            let mut rng: StdRng = SeedableRng::seed_from_u64(random_seed);
+            let mut rng = Pcg32::new(random_seed);
             let points = (0..h)
-                .map(|_| (0..w).map(|_| rng.gen_range(1..10)).collect::<Vec<_>>())
+                .map(|_| {
+                    (0..w)
+                        .map(|_| rng.gen_range(1..10) as i32)
+                        .collect::<Vec<_>>()
+                })
                 .collect::<Vec<_>>();
             let characters = (0..num_characters)
                 .map(|_| Character { y: 0, x: 0 })
                 .collect::<Vec<_>>();
 
-            return State {
+            State {
                 h,
                 w,
                 max_turn,
@@ -56,7 +248,8 @@ pub mod automovemaze {
                 score: 0,
                 points,
                 characters,
-            };
+                rule,
+            }
         }
 
         pub fn h(&self) -> usize {
@@ -83,36 +276,61 @@ pub mod automovemaze {
             self.score
         }
 
+        pub fn rule(&self) -> &MovementRule {
+            &self.rule
+        }
+
+        // Points left on the cell (y, x)
+        pub fn point(&self, y: usize, x: usize) -> i32 {
+            self.points[y][x]
+        }
+
         pub fn characters(&self) -> &[Character] {
             self.characters.as_ref()
         }
 
+        pub fn placement(&self) -> Placement {
+            Placement::new(self.characters.iter().map(|c| (c.y, c.x)).collect())
+        }
+
         pub fn is_game_over(&self) -> bool {
-            return self.turn >= self.max_turn;
+            self.turn >= self.max_turn
         }
 
         pub fn valid_actions(&self, character_index: usize) -> Vec<Action> {
+            let mut actions = Vec::with_capacity(9);
+            self.legal_actions_into(character_index, &mut actions);
+
+            actions
+        }
+
+        // Replaces the contents of `actions` with the valid actions of the
+        // character, reusing its allocation
+        pub fn legal_actions_into(&self, character_index: usize, actions: &mut Vec<Action>) {
             if self.num_characters() <= character_index {
                 panic!("invalid character index");
             }
 
             let character = &self.characters[character_index];
-            let mut actions = Vec::with_capacity(4);
+            actions.clear();
+            self.for_each_move(character.y, character.x, |action, _| actions.push(action));
+        }
 
-            if character.y > 0 {
-                actions.push(Action::Up);
-            }
-            if character.y < self.h - 1 {
-                actions.push(Action::Down);
-            }
-            if character.x > 0 {
-                actions.push(Action::Left);
-            }
-            if character.x < self.w - 1 {
-                actions.push(Action::Right);
-            }
+        // Calls f on each move allowed by the rule from (y, x) with its
+        // destination, in comparison order
+        fn for_each_move<F: FnMut(Action, (usize, usize))>(&self, y: usize, x: usize, mut f: F) {
+            let (h, w) = (self.h as i32, self.w as i32);
 
-            return actions;
+            self.rule.for_each_action(|action| {
+                let (dy, dx) = action.delta();
+                let (y, x) = (y as i32 + dy, x as i32 + dx);
+
+                if self.rule.wrap_around {
+                    f(action, (y.rem_euclid(h) as usize, x.rem_euclid(w) as usize));
+                } else if 0 <= y && y < h && 0 <= x && x < w {
+                    f(action, (y as usize, x as usize));
+                }
+            });
         }
 
         pub fn set_character_coord(&mut self, i: usize, x: usize, y: usize) {
@@ -120,13 +338,36 @@ pub mod automovemaze {
                 panic!("invalid character index");
             }
 
-            let mut character = &mut self.characters[i];
+            let character = &mut self.characters[i];
             character.y = y;
             character.x = x;
 
             self.points[character.y][character.x] = 0;
         }
 
+        // Place the characters on a copy of this state and play it to the end
+        pub fn simulate(&self, placement: &Placement) -> State {
+            if self.num_characters() != placement.len() {
+                panic!("invalid placement");
+            }
+
+            let mut state = self.clone();
+
+            for (i, &(y, x)) in placement.coords().iter().enumerate() {
+                state.set_character_coord(i, x, y);
+            }
+
+            while !state.is_game_over() {
+                state.advance();
+            }
+
+            state
+        }
+
+        pub fn evaluate(&self, placement: &Placement) -> i32 {
+            self.simulate(placement).score()
+        }
+
         pub fn advance(&mut self) {
             for i in 0..self.num_characters() {
                 self.advance_character(i);
@@ -140,39 +381,288 @@ pub mod automovemaze {
                 panic!("invalid character index");
             }
 
-            let (new_x, new_y) = self
-                .valid_actions(i)
-                .iter()
-                .map(|action| {
-                    let character = &self.characters[i];
-                    let (dy, dx) = match action {
-                        Action::Up => (-1, 0),
-                        Action::Down => (1, 0),
-                        Action::Left => (0, -1),
-                        Action::Right => (0, 1),
-                    };
-
-                    let y = (character.y as i32 + dy) as usize;
-                    let x = (character.x as i32 + dx) as usize;
-
-                    (y, x)
-                })
-                .max_by_key(|(y, x)| {
-                    return self.points[*y][*x];
-                })
-                .unwrap();
+            let character = &self.characters[i];
+            let mut best: Option<(usize, usize)> = None;
+            self.for_each_move(character.y, character.x, |_, (y, x)| {
+                best = match best {
+                    Some((by, bx))
+                        if !self.rule.prefers(self.points[y][x], self.points[by][bx]) =>
+                    {
+                        Some((by, bx))
+                    }
+                    _ => Some((y, x)),
+                };
+            });
+            let (new_y, new_x) = best.unwrap();
 
-            let mut character = &mut self.characters[i];
+            let character = &mut self.characters[i];
             character.y = new_y;
             character.x = new_x;
 
-            let point = &mut self.points[character.x][character.y];
+            let point = &mut self.points[character.y][character.x];
             self.score += *point;
 
             *point = 0;
         }
     }
 
+    // Evaluates placements on a fixed board without cloning the state: the
+    // board is kept in a flat buffer and only the cells zeroed by the previous
+    // simulation are restored before the next one. The destinations allowed by
+    // the movement rule are computed once per cell.
+    enum Event {
+        Inspect,
+        Take,
+    }
+
+    pub struct Evaluator {
+        w: usize,
+        rule: MovementRule,
+        turns: i32,
+        score: i32,
+        initial_points: Vec<i32>,
+        points: Vec<i32>,
+        dirty: Vec<usize>,
+        characters: Vec<usize>,
+        // Destinations of cell i are moves[move_offsets[i]..move_offsets[i + 1]]
+        moves: Vec<usize>,
+        move_offsets: Vec<usize>,
+    }
+
+    impl Evaluator {
+        pub fn new(state: &State) -> Self {
+            let initial_points = state.points.concat();
+            let mut moves = Vec::new();
+            let mut move_offsets = vec![0];
+
+            for y in 0..state.h {
+                for x in 0..state.w {
+                    state.for_each_move(y, x, |_, (y, x)| moves.push(y * state.w + x));
+                    move_offsets.push(moves.len());
+                }
+            }
+
+            Evaluator {
+                w: state.w,
+                rule: state.rule.clone(),
+                turns: state.max_turn - state.turn,
+                score: state.score,
+                points: initial_points.clone(),
+                initial_points,
+                dirty: Vec::with_capacity(state.h * state.w),
+                characters: Vec::with_capacity(state.num_characters()),
+                moves,
+                move_offsets,
+            }
+        }
+
+        pub fn evaluate(&mut self, placement: &Placement) -> i32 {
+            self.run(placement, |_, _, _, _| {})
+        }
+
+        // Simulates the placement, reporting observe(event, turn, character,
+        // cell) for each cell a character compares before moving and each cell
+        // whose points it clears; the initial placement is turn -1
+        fn run<F>(&mut self, placement: &Placement, mut observe: F) -> i32
+        where
+            F: FnMut(Event, i32, usize, usize),
+        {
+            for i in self.dirty.drain(..) {
+                self.points[i] = self.initial_points[i];
+            }
+
+            self.characters.clear();
+            for (c, &(y, x)) in placement.coords().iter().enumerate() {
+                let i = y * self.w + x;
+                self.characters.push(i);
+
+                if self.points[i] != 0 {
+                    observe(Event::Take, -1, c, i);
+                }
+                self.clear(i);
+            }
+
+            let mut score = self.score;
+
+            for turn in 0..self.turns {
+                for c in 0..self.characters.len() {
+                    let i = self.characters[c];
+                    for &j in &self.moves[self.move_offsets[i]..self.move_offsets[i + 1]] {
+                        observe(Event::Inspect, turn, c, j);
+                    }
+
+                    let next = self.next_cell(i);
+                    self.characters[c] = next;
+
+                    if self.points[next] != 0 {
+                        observe(Event::Take, turn, c, next);
+                    }
+                    score += self.points[next];
+                    self.clear(next);
+                }
+            }
+
+            score
+        }
+
+        // Same choice as State::advance_character
+        fn next_cell(&self, i: usize) -> usize {
+            self.moves[self.move_offsets[i]..self.move_offsets[i + 1]]
+                .iter()
+                .copied()
+                .reduce(|best, j| {
+                    if self.rule.prefers(self.points[j], self.points[best]) {
+                        j
+                    } else {
+                        best
+                    }
+                })
+                .unwrap()
+        }
+
+        fn clear(&mut self, i: usize) {
+            if self.points[i] != 0 {
+                self.points[i] = 0;
+                self.dirty.push(i);
+            }
+        }
+    }
+
+    // Finds the best placement by trying every one of them. Characters move in
+    // index order, so the order of a placement matters when they compete for
+    // cells. With reduce_permutations each multiset of cells is simulated once
+    // in sorted order, and its other orderings are only tried if some character
+    // inspected a cell whose points another one took in the same turn;
+    // otherwise every character sees the same board in any order and the score
+    // is unchanged. This pays off when characters are sparse on the board.
+    pub struct ExhaustiveSolver {
+        reduce_permutations: bool,
+    }
+
+    impl ExhaustiveSolver {
+        pub fn new(reduce_permutations: bool) -> Self {
+            ExhaustiveSolver {
+                reduce_permutations,
+            }
+        }
+
+        // Return the best placement and its score, the first one found on ties
+        pub fn solve(&self, state: &State) -> (Placement, i32) {
+            let cells = state.h * state.w;
+            let n = state.num_characters();
+            let reduce = self.reduce_permutations && n <= u64::BITS as usize;
+            let to_placement = |indices: &[usize]| {
+                Placement::new(indices.iter().map(|i| (i / state.w, i % state.w)).collect())
+            };
+
+            let mut evaluator = Evaluator::new(state);
+            // Characters that inspected and took each cell in the current turn
+            let mut touched = vec![(-1, 0u64, 0u64); cells];
+            let mut indices = vec![0; n];
+            let mut best: Option<(Vec<usize>, i32)> = None;
+
+            let mut update = |indices: &[usize], score: i32| {
+                if best
+                    .as_ref()
+                    .is_none_or(|(_, best_score)| *best_score < score)
+                {
+                    best = Some((indices.to_vec(), score));
+                }
+            };
+
+            loop {
+                if reduce {
+                    touched.fill((-1, 0, 0));
+
+                    let mut interacted = false;
+                    let score = evaluator.run(&to_placement(&indices), |event, turn, c, i| {
+                        let bit = 1 << c;
+                        let (touched_turn, inspected_by, taken_by) = &mut touched[i];
+
+                        if *touched_turn != turn {
+                            *touched_turn = turn;
+                            *inspected_by = 0;
+                            *taken_by = 0;
+                        }
+
+                        match event {
+                            Event::Inspect => *inspected_by |= bit,
+                            Event::Take => *taken_by |= bit,
+                        }
+
+                        interacted |= *inspected_by != 0
+                            && *taken_by != 0
+                            && (*inspected_by | *taken_by).count_ones() > 1;
+                    });
+                    update(&indices, score);
+
+                    if interacted {
+                        let mut permutation = indices.clone();
+                        while next_permutation(&mut permutation) {
+                            let score = evaluator.evaluate(&to_placement(&permutation));
+                            update(&permutation, score);
+                        }
+                    }
+                } else {
+                    let score = evaluator.evaluate(&to_placement(&indices));
+                    update(&indices, score);
+                }
+
+                if !next_indices(&mut indices, cells, reduce) {
+                    break;
+                }
+            }
+
+            let (indices, score) = best.unwrap();
+
+            (to_placement(&indices), score)
+        }
+    }
+
+    impl Agent for ExhaustiveSolver {
+        fn play_game(&mut self, state: &State) -> (Placement, State) {
+            let (placement, _) = self.solve(state);
+            let final_state = state.simulate(&placement);
+
+            (placement, final_state)
+        }
+    }
+
+    // Advance to the next tuple of cell indices in lexicographic order, only
+    // visiting non-decreasing tuples if sorted is set
+    fn next_indices(indices: &mut [usize], cells: usize, sorted: bool) -> bool {
+        for k in (0..indices.len()).rev() {
+            if indices[k] + 1 < cells {
+                indices[k] += 1;
+                let reset = if sorted { indices[k] } else { 0 };
+                indices[k + 1..].fill(reset);
+
+                return true;
+            }
+        }
+
+        false
+    }
+
+    // Advance to the next distinct permutation in lexicographic order
+    fn next_permutation(indices: &mut [usize]) -> bool {
+        let Some(k) = (1..indices.len())
+            .rev()
+            .find(|&k| indices[k - 1] < indices[k])
+        else {
+            return false;
+        };
+        let l = (k..indices.len())
+            .rev()
+            .find(|&l| indices[k - 1] < indices[l])
+            .unwrap();
+
+        indices.swap(k - 1, l);
+        indices[k..].reverse();
+
+        true
+    }
+
     impl Debug for State {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             writeln!(f, "turn:\t{}", self.turn)?;
@@ -183,8 +673,8 @@ pub mod automovemaze {
                     .map(|x| {
                         if self.characters.iter().any(|c| c.x == x && c.y == y) {
                             "@".to_string()
-                        } else if 0 < self.points[x][y] {
-                            self.points[x][y].to_string()
+                        } else if 0 < self.points[y][x] {
+                            self.points[y][x].to_string()
                         } else {
                             ".".to_string()
                         }
@@ -198,4 +688,461 @@ pub mod automovemaze {
             Ok(())
         }
     }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct ParseError(pub String);
+
+    impl std::fmt::Display for ParseError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl std::error::Error for ParseError {}
+
+    // Reads the Debug rendering back, preceded by the settings it does not
+    // show. The rule line is optional and lists the enabled flags; ties are
+    // always broken by the default order.
+    //
+    //   max_turn:	5
+    //   characters:	2
+    //   rule:	eight_directions wrap_around
+    //   placement:	0,2 0,2
+    //   turn:	0
+    //   score:	0
+    //   12@45
+    //   ...
+    //
+    // The placement line gives each character's y,x in index order, which
+    // decides the order they move in, since several can share an @ cell.
+    // The @ cells are read as empty and must be exactly the listed ones.
+    // Without a placement line the board has no @ and every character is
+    // left unplaced at (0, 0), as State::new does.
+    impl FromStr for State {
+        type Err = ParseError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let mut lines = s.lines().filter(|line| !line.trim().is_empty()).peekable();
+            let max_turn = parse_header(lines.next(), "max_turn")?;
+            let num_characters = parse_header(lines.next(), "characters")?;
+
+            let mut rule = MovementRule::default();
+            if lines.peek().is_some_and(|line| line.starts_with("rule:")) {
+                let flags = parse_header::<String>(lines.next(), "rule")?;
+                for flag in flags.split_whitespace() {
+                    match flag {
+                        "eight_directions" => rule.eight_directions = true,
+                        "allow_stay" => rule.allow_stay = true,
+                        "wrap_around" => rule.wrap_around = true,
+                        _ => return Err(ParseError(format!("unknown rule {:?}", flag))),
+                    }
+                }
+            }
+
+            let mut characters = vec![Character { y: 0, x: 0 }; num_characters];
+            let placed = lines
+                .peek()
+                .is_some_and(|line| line.starts_with("placement:"));
+            if placed {
+                let coords = parse_header::<String>(lines.next(), "placement")?;
+                characters = coords
+                    .split_whitespace()
+                    .map(|coord| {
+                        coord
+                            .split_once(',')
+                            .and_then(|(y, x)| {
+                                Some(Character {
+                                    y: y.parse().ok()?,
+                                    x: x.parse().ok()?,
+                                })
+                            })
+                            .ok_or_else(|| {
+                                ParseError(format!("cannot parse coordinate {:?}", coord))
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                if characters.len() != num_characters {
+                    return Err(ParseError(format!(
+                        "expected {} characters in the placement, found {}",
+                        num_characters,
+                        characters.len()
+                    )));
+                }
+            }
+
+            let turn = parse_header(lines.next(), "turn")?;
+            let score = parse_header(lines.next(), "score")?;
+
+            let mut points = Vec::new();
+            let mut marked = Vec::new();
+            for (y, line) in lines.enumerate() {
+                let row = line
+                    .trim()
+                    .chars()
+                    .enumerate()
+                    .map(|(x, c)| match c {
+                        '1'..='9' => Ok(c as i32 - '0' as i32),
+                        '.' => Ok(0),
+                        '@' => {
+                            marked.push((y, x));
+                            Ok(0)
+                        }
+                        _ => Err(ParseError(format!("unexpected {:?} on the board", c))),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                points.push(row);
+            }
+
+            if points.is_empty() || points.iter().any(|row| row.len() != points[0].len()) {
+                return Err(ParseError(
+                    "the board must be a non-empty rectangle".to_string(),
+                ));
+            }
+            if placed {
+                let mut coords = characters.iter().map(|c| (c.y, c.x)).collect::<Vec<_>>();
+                coords.sort_unstable();
+                coords.dedup();
+                if coords != marked {
+                    return Err(ParseError(
+                        "the @ cells do not match the placement".to_string(),
+                    ));
+                }
+            } else if !marked.is_empty() {
+                return Err(ParseError(
+                    "a board with @ cells needs a placement line".to_string(),
+                ));
+            }
+
+            Ok(State {
+                h: points.len(),
+                w: points[0].len(),
+                max_turn,
+                turn,
+                score,
+                points,
+                characters,
+                rule,
+            })
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct StateData {
+        max_turn: i32,
+        turn: i32,
+        score: i32,
+        points: Vec<Vec<i32>>,
+        characters: Vec<Character>,
+        rule: MovementRule,
+    }
+
+    #[cfg(feature = "serde")]
+    impl From<State> for StateData {
+        fn from(state: State) -> Self {
+            StateData {
+                max_turn: state.max_turn,
+                turn: state.turn,
+                score: state.score,
+                points: state.points,
+                characters: state.characters,
+                rule: state.rule,
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    impl TryFrom<StateData> for State {
+        type Error = ParseError;
+
+        fn try_from(data: StateData) -> Result<Self, Self::Error> {
+            let points = data.points;
+            if points.is_empty() || points.iter().any(|row| row.len() != points[0].len()) {
+                return Err(ParseError(
+                    "points must be a non-empty rectangle".to_string(),
+                ));
+            }
+
+            let (h, w) = (points.len(), points[0].len());
+            if data.characters.iter().any(|c| c.y >= h || c.x >= w) {
+                return Err(ParseError("a character is outside the board".to_string()));
+            }
+
+            Ok(State {
+                h,
+                w,
+                max_turn: data.max_turn,
+                turn: data.turn,
+                score: data.score,
+                points,
+                characters: data.characters,
+                rule: data.rule,
+            })
+        }
+    }
+
+    // The value of a `key:\tvalue` line
+    fn parse_header<T: FromStr>(line: Option<&str>, key: &str) -> Result<T, ParseError> {
+        let value = line
+            .and_then(|line| line.split_once(':'))
+            .filter(|(k, _)| k.trim() == key)
+            .map(|(_, value)| value.trim())
+            .ok_or_else(|| ParseError(format!("expected a {} line", key)))?;
+
+        value
+            .parse()
+            .map_err(|_| ParseError(format!("cannot parse {} {:?}", key, value)))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use rng::Pcg32;
+
+        use super::{
+            Action, Evaluator, ExhaustiveSolver, MovementRule, Placement, State, TieBreak,
+        };
+
+        fn rules() -> Vec<MovementRule> {
+            vec![
+                MovementRule::default(),
+                MovementRule {
+                    tie_break: TieBreak::Priority(vec![Action::Right, Action::Left]),
+                    ..Default::default()
+                },
+                MovementRule {
+                    allow_stay: true,
+                    ..Default::default()
+                },
+                MovementRule {
+                    eight_directions: true,
+                    ..Default::default()
+                },
+                MovementRule {
+                    wrap_around: true,
+                    ..Default::default()
+                },
+                MovementRule {
+                    eight_directions: true,
+                    allow_stay: true,
+                    wrap_around: true,
+                    tie_break: TieBreak::Priority(vec![Action::Stay, Action::DownRight]),
+                },
+            ]
+        }
+
+        #[test]
+        fn valid_actions() {
+            let counts = rules()
+                .into_iter()
+                .map(|rule| State::new(0, 5, 5, 1, 5, rule).valid_actions(0).len())
+                .collect::<Vec<_>>();
+
+            assert_eq!(counts, vec![2, 2, 3, 3, 4, 9]);
+        }
+
+        #[test]
+        fn priority_order() {
+            let rule = MovementRule {
+                allow_stay: true,
+                tie_break: TieBreak::Priority(vec![Action::Stay, Action::Left]),
+                ..Default::default()
+            };
+
+            assert_eq!(
+                rule.actions(),
+                vec![
+                    Action::Stay,
+                    Action::Left,
+                    Action::Up,
+                    Action::Down,
+                    Action::Right
+                ]
+            );
+        }
+
+        // Rows and columns used to be swapped when a character moved, which
+        // only shows on a board that is not square
+        #[test]
+        fn non_square_board() {
+            let mut state = State::new(0, 1, 3, 1, 2, MovementRule::default());
+            state.points = vec![vec![0, 5, 7]];
+            state.set_character_coord(0, 0, 0);
+            state.advance();
+            state.advance();
+
+            assert_eq!(state.score(), 12);
+            assert_eq!(format!("{:?}", state).lines().last(), Some("..@"));
+        }
+
+        #[test]
+        fn evaluator_matches_simulation() {
+            let mut rng = Pcg32::new(0);
+
+            for rule in rules() {
+                for (seed, (h, w)) in [(5, 5), (3, 7), (8, 2), (50, 50)].into_iter().enumerate() {
+                    let state = State::new(seed as u64, h, w, 3, 10, rule.clone());
+                    let mut evaluator = Evaluator::new(&state);
+
+                    for _ in 0..100 {
+                        let placement = Placement::random(&mut rng, &state);
+                        assert_eq!(evaluator.evaluate(&placement), state.evaluate(&placement));
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn random_neighbor() {
+            let mut rng = Pcg32::new(0);
+            let state = State::new(0, 3, 7, 3, 10, MovementRule::default());
+            let placement = Placement::random(&mut rng, &state);
+
+            for _ in 0..100 {
+                let neighbor = placement.random_neighbor(&mut rng, &state);
+                let moved = (0..placement.len())
+                    .filter(|&i| neighbor.coord(i) != placement.coord(i))
+                    .count();
+
+                assert_eq!(neighbor.len(), placement.len());
+                assert!(moved <= 1);
+                assert!(neighbor.coords().iter().all(|&(y, x)| y < 3 && x < 7));
+            }
+        }
+
+        #[test]
+        fn exhaustive_solver_reduction() {
+            for rule in rules() {
+                for seed in 0..5 {
+                    let state = State::new(seed, 4, 4, 3, 4, rule.clone());
+                    let (placement, score) = ExhaustiveSolver::new(true).solve(&state);
+
+                    assert_eq!(state.evaluate(&placement), score);
+                    assert_eq!(ExhaustiveSolver::new(false).solve(&state).1, score);
+                }
+            }
+        }
+
+        #[test]
+        fn parse_state() {
+            let text = "max_turn:\t2\ncharacters:\t2\nrule:\tallow_stay\nplacement:\t2,2 0,0\n\
+                        turn:\t0\nscore:\t0\n\
+                        @19\n\
+                        2.3\n\
+                        45@\n";
+            let mut state = text.parse::<State>().unwrap();
+
+            assert_eq!((state.h(), state.w(), state.max_turn()), (3, 3, 2));
+            assert!(state.rule().allow_stay && !state.rule().eight_directions);
+            assert_eq!(state.placement(), Placement::new(vec![(2, 2), (0, 0)]));
+
+            state.advance();
+            assert_eq!(state.score(), 7);
+
+            let text = format!(
+                "max_turn:\t2\ncharacters:\t2\nrule:\tallow_stay\n{}\n{:?}",
+                placement_line(&state),
+                state
+            );
+            let parsed = text.parse::<State>().unwrap();
+            assert_eq!(format!("{:?}", parsed), format!("{:?}", state));
+            assert_eq!(parsed.placement(), state.placement());
+        }
+
+        fn placement_line(state: &State) -> String {
+            let coords = state
+                .placement()
+                .coords()
+                .iter()
+                .map(|(y, x)| format!("{},{}", y, x))
+                .collect::<Vec<_>>();
+
+            format!("placement:\t{}", coords.join(" "))
+        }
+
+        #[test]
+        fn parse_shared_cell() {
+            let mut state = State::new(3, 4, 4, 3, 4, MovementRule::default());
+            state.set_character_coord(0, 1, 2);
+            state.set_character_coord(1, 3, 0);
+            state.set_character_coord(2, 1, 2);
+
+            let text = format!(
+                "max_turn:\t4\ncharacters:\t3\n{}\n{:?}",
+                placement_line(&state),
+                state
+            );
+            let mut parsed = text.parse::<State>().unwrap();
+            assert_eq!(parsed.placement(), state.placement());
+
+            while !state.is_game_over() {
+                state.advance();
+                parsed.advance();
+            }
+            assert_eq!(parsed.score(), state.score());
+            assert_eq!(parsed.placement(), state.placement());
+        }
+
+        #[test]
+        fn parse_unplaced_state() {
+            let text = "max_turn:\t5\ncharacters:\t3\nturn:\t0\nscore:\t0\n123\n456\n789\n";
+            let state = text.parse::<State>().unwrap();
+
+            assert_eq!(state.num_characters(), 3);
+            assert_eq!(
+                state.evaluate(&Placement::new(vec![(0, 0), (1, 1), (2, 2)])),
+                state
+                    .simulate(&Placement::new(vec![(0, 0), (1, 1), (2, 2)]))
+                    .score()
+            );
+            assert!(text.replace("123", "1@3").parse::<State>().is_err());
+            assert!(text.replace("456", "45").parse::<State>().is_err());
+
+            let placed = text.replace("\nturn:", "\nplacement:\t0,1 0,1 1,0\nturn:");
+            assert!(placed.parse::<State>().is_err());
+            let placed = placed.replace("123", "1@3").replace("456", "@56");
+            assert_eq!(
+                placed.parse::<State>().unwrap().placement(),
+                Placement::new(vec![(0, 1), (0, 1), (1, 0)])
+            );
+            assert!(placed
+                .replace("0,1 0,1 1,0", "0,1 1,0")
+                .parse::<State>()
+                .is_err());
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn json_schema() {
+            let rule = MovementRule {
+                tie_break: TieBreak::Priority(vec![Action::Stay, Action::UpLeft]),
+                ..Default::default()
+            };
+            let mut state = State::new(0, 2, 2, 1, 3, rule);
+            state.set_character_coord(0, 0, 1);
+            let json = serde_json::to_string(&state).unwrap();
+
+            assert_eq!(
+                json,
+                format!(
+                    r#"{{"max_turn":3,"turn":0,"score":0,"points":{},"characters":[{{"y":1,"x":0}}],"rule":{{"eight_directions":false,"allow_stay":false,"wrap_around":false,"tie_break":{{"priority":["stay","up_left"]}}}}}}"#,
+                    serde_json::to_string(&state.points).unwrap()
+                )
+            );
+
+            let parsed = serde_json::from_str::<State>(&json).unwrap();
+            assert_eq!(format!("{:?}", parsed), format!("{:?}", state));
+            assert_eq!((parsed.h(), parsed.w()), (2, 2));
+            assert_eq!(parsed.rule(), state.rule());
+            assert_eq!(parsed.placement(), state.placement());
+
+            let json = json.replace(r#""y":1"#, r#""y":2"#);
+            assert!(serde_json::from_str::<State>(&json).is_err());
+            assert_eq!(
+                serde_json::to_string(&Placement::new(vec![(1, 2)])).unwrap(),
+                r#"{"coords":[[1,2]]}"#
+            );
+        }
+    }
 }
@@ -0,0 +1,48 @@
+use lib::automovemaze;
+use lib::automovemaze::Placement;
+use rng::Pcg32;
+
+pub struct RandomAgent {
+    rng: Pcg32,
+}
+
+impl RandomAgent {
+    pub fn new(seed: u64) -> Self {
+        RandomAgent {
+            rng: Pcg32::new(seed),
+        }
+    }
+}
+
+impl automovemaze::Agent for RandomAgent {
+    fn play_game(&mut self, state: &automovemaze::State) -> (Placement, automovemaze::State) {
+        let placement = Placement::random(&mut self.rng, state);
+        let final_state = state.simulate(&placement);
+
+        (placement, final_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use harness::Harness;
+    use lib::automovemaze;
+    use lib::automovemaze::{Agent, MovementRule};
+    use rng::Pcg32;
+
+    #[test]
+    fn score() {
+        let mut harness = Harness::new((0..100).collect());
+        harness.run("random", |seed| {
+            let mut rng = Pcg32::new(seed);
+            let initial_state =
+                automovemaze::State::new(rng.next_u64(), 5, 5, 3, 5, MovementRule::default());
+            let (_, last_state) = super::RandomAgent::new(rng.next_u64()).play_game(&initial_state);
+
+            last_state.score()
+        });
+
+        println!("{}", harness);
+        assert_eq!(harness.results()[0].total_score(), 7716);
+    }
+}
@@ -0,0 +1,258 @@
+use std::thread;
+
+use lib::automovemaze::Agent;
+use lib::automovemaze::{Evaluator, Placement, State};
+use rng::Pcg32;
+
+// The temperature falls linearly from start to end over the iterations
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TemperatureRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulatedAnnealingParams {
+    pub count: u32,
+    pub temp_range: TemperatureRange,
+}
+
+impl SimulatedAnnealingParams {
+    fn new(count: u32, temp_start: u32, temp_end: u32) -> Self {
+        SimulatedAnnealingParams {
+            count,
+            temp_range: TemperatureRange {
+                start: temp_start,
+                end: temp_end,
+            },
+        }
+    }
+}
+
+pub struct SimulatedAnnealingAgent {
+    rng: Pcg32,
+    params: SimulatedAnnealingParams,
+}
+
+impl SimulatedAnnealingAgent {
+    pub fn new(seed: u64, count: u32, temp_start: u32, temp_end: u32) -> Self {
+        Self::with_params(
+            seed,
+            SimulatedAnnealingParams::new(count, temp_start, temp_end),
+        )
+    }
+
+    pub fn with_params(seed: u64, params: SimulatedAnnealingParams) -> Self {
+        SimulatedAnnealingAgent {
+            rng: Pcg32::new(seed),
+            params,
+        }
+    }
+
+    pub fn params(&self) -> &SimulatedAnnealingParams {
+        &self.params
+    }
+}
+
+impl Agent for SimulatedAnnealingAgent {
+    fn play_game(&mut self, state: &State) -> (Placement, State) {
+        let mut evaluator = Evaluator::new(state);
+        let mut placement = state.placement();
+        let mut score = evaluator.evaluate(&placement);
+        let mut best_placement = placement.clone();
+        let mut best_score = score;
+
+        let SimulatedAnnealingParams { count, temp_range } = self.params;
+
+        for i in 0..count {
+            let neighbor = placement.random_neighbor(&mut self.rng, state);
+            let neighbor_score = evaluator.evaluate(&neighbor);
+
+            let TemperatureRange {
+                start: temp_start,
+                end: temp_end,
+            } = temp_range;
+
+            let delta = neighbor_score - score;
+            let temp = temp_start as f64
+                + (temp_end as f64 - temp_start as f64) * (i as f64 / count as f64);
+            let prob = acceptance_probability(delta, temp);
+
+            if best_score < neighbor_score {
+                best_placement = neighbor.clone();
+                best_score = neighbor_score;
+            }
+
+            if delta > 0 || self.rng.gen_bool(prob) {
+                placement = neighbor;
+                score = neighbor_score;
+            }
+        }
+
+        let final_state = state.simulate(&best_placement);
+
+        (best_placement, final_state)
+    }
+}
+
+// Chance of moving to a neighbour that scores delta more than the current
+// state: certain when it is no worse, exp(delta / temp) when it is worse
+fn acceptance_probability(delta: i32, temp: f64) -> f64 {
+    (delta as f64 / temp).exp().min(1.0)
+}
+
+// Chains run when no thread count is given. It is fixed rather than taken
+// from the machine since results depend on it.
+pub const DEFAULT_THREADS: usize = 4;
+
+// Runs one annealing chain per worker thread, each with its own RNG seeded
+// from the master seed, and keeps the best result. Ties go to the chain with
+// the lowest index, so the result only depends on the seed and thread count.
+pub struct MultiStartSimulatedAnnealingAgent {
+    seed: u64,
+    num_threads: usize,
+    params: SimulatedAnnealingParams,
+}
+
+impl MultiStartSimulatedAnnealingAgent {
+    pub fn new(seed: u64, num_threads: usize, count: u32, temp_start: u32, temp_end: u32) -> Self {
+        Self::with_params(
+            seed,
+            num_threads,
+            SimulatedAnnealingParams::new(count, temp_start, temp_end),
+        )
+    }
+
+    // Every chain runs with the same params
+    pub fn with_params(seed: u64, num_threads: usize, params: SimulatedAnnealingParams) -> Self {
+        assert!(num_threads > 0, "at least one annealing chain is needed");
+
+        MultiStartSimulatedAnnealingAgent {
+            seed,
+            num_threads,
+            params,
+        }
+    }
+
+    pub fn params(&self) -> &SimulatedAnnealingParams {
+        &self.params
+    }
+}
+
+impl Agent for MultiStartSimulatedAnnealingAgent {
+    fn play_game(&mut self, state: &State) -> (Placement, State) {
+        let mut master_rng = Pcg32::new(self.seed);
+        let seeds = (0..self.num_threads)
+            .map(|_| master_rng.next_u64())
+            .collect::<Vec<_>>();
+        let params = self.params;
+
+        let results = thread::scope(|scope| {
+            let handles = seeds
+                .into_iter()
+                .map(|seed| {
+                    scope.spawn(move || {
+                        SimulatedAnnealingAgent::with_params(seed, params).play_game(state)
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        results
+            .into_iter()
+            .reduce(|best, result| {
+                if best.1.score() < result.1.score() {
+                    result
+                } else {
+                    best
+                }
+            })
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use harness::Harness;
+    use lib::automovemaze::Agent;
+    use lib::automovemaze::{MovementRule, State};
+    use rng::Pcg32;
+
+    #[cfg(feature = "serde")]
+    use super::SimulatedAnnealingParams;
+    use super::{MultiStartSimulatedAnnealingAgent, SimulatedAnnealingAgent};
+
+    #[test]
+    fn score() {
+        let mut harness = Harness::new((0..100).collect());
+        harness.run("simulated-annealing", |seed| {
+            let mut rng = Pcg32::new(seed);
+            let initial_state = State::new(rng.next_u64(), 5, 5, 3, 5, MovementRule::default());
+            let (_, last_state) = SimulatedAnnealingAgent::new(rng.next_u64(), 10000, 500, 10)
+                .play_game(&initial_state);
+
+            last_state.score()
+        });
+        harness.run("simulated-annealing (4 chains)", |seed| {
+            let mut rng = Pcg32::new(seed);
+            let initial_state = State::new(rng.next_u64(), 5, 5, 3, 5, MovementRule::default());
+            let (_, last_state) =
+                MultiStartSimulatedAnnealingAgent::new(rng.next_u64(), 4, 10000, 500, 10)
+                    .play_game(&initial_state);
+
+            last_state.score()
+        });
+
+        println!("{}", harness);
+        assert_eq!(harness.results()[0].total_score(), 9560);
+        assert_eq!(harness.results()[1].total_score(), 9563);
+    }
+
+    #[test]
+    fn acceptance_probability() {
+        assert_eq!(super::acceptance_probability(5, 10.0), 1.0);
+        assert_eq!(super::acceptance_probability(0, 10.0), 1.0);
+        assert!((super::acceptance_probability(-10, 10.0) - (-1.0f64).exp()).abs() < 1e-12);
+        assert!(super::acceptance_probability(-100, 1.0) < 1e-40);
+    }
+
+    #[test]
+    fn multi_start_is_reproducible() {
+        for seed in 0..10 {
+            let mut rng = Pcg32::new(seed);
+            let initial_state = State::new(rng.next_u64(), 5, 5, 3, 5, MovementRule::default());
+            let seed = rng.next_u64();
+            let (placement, last_state) =
+                MultiStartSimulatedAnnealingAgent::new(seed, 4, 1000, 500, 10)
+                    .play_game(&initial_state);
+            let (other_placement, other_last_state) =
+                MultiStartSimulatedAnnealingAgent::new(seed, 4, 1000, 500, 10)
+                    .play_game(&initial_state);
+
+            assert_eq!(placement, other_placement);
+            assert_eq!(last_state.score(), other_last_state.score());
+        }
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_schema() {
+        let agent = MultiStartSimulatedAnnealingAgent::new(0, 4, 10000, 500, 10);
+        let json = serde_json::to_string(agent.params()).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"count":10000,"temp_range":{"start":500,"end":10}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<SimulatedAnnealingParams>(&json).unwrap(),
+            *agent.params()
+        );
+    }
+}
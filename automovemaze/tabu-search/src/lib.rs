@@ -0,0 +1,136 @@
+use lib::automovemaze::Agent;
+use lib::automovemaze::{Evaluator, Placement, State};
+use rng::Pcg32;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TabuSearchParams {
+    pub count: u32,
+    pub num_neighbors: usize,
+    pub tenure: u32,
+}
+
+pub struct TabuSearchAgent {
+    rng: Pcg32,
+    params: TabuSearchParams,
+}
+
+// Move a character to another cell
+struct Move {
+    character: usize,
+    coord: (usize, usize),
+}
+
+impl TabuSearchAgent {
+    pub fn new(seed: u64, count: u32, num_neighbors: usize, tenure: u32) -> Self {
+        Self::with_params(
+            seed,
+            TabuSearchParams {
+                count,
+                num_neighbors,
+                tenure,
+            },
+        )
+    }
+
+    pub fn with_params(seed: u64, params: TabuSearchParams) -> Self {
+        TabuSearchAgent {
+            rng: Pcg32::new(seed),
+            params,
+        }
+    }
+
+    pub fn params(&self) -> &TabuSearchParams {
+        &self.params
+    }
+
+    fn generate_move(&mut self, state: &State) -> Move {
+        Move {
+            character: self.rng.gen_range(0..state.num_characters()),
+            coord: (
+                self.rng.gen_range(0..state.h()),
+                self.rng.gen_range(0..state.w()),
+            ),
+        }
+    }
+}
+
+impl Agent for TabuSearchAgent {
+    fn play_game(&mut self, state: &State) -> (Placement, State) {
+        let mut evaluator = Evaluator::new(state);
+        let mut placement = state.placement();
+        let mut best_placement = placement.clone();
+        let mut best_score = evaluator.evaluate(&placement);
+
+        // Iteration until which each character may not be moved, and until
+        // which no character may move back onto each cell
+        let mut character_tabu = vec![0; state.num_characters()];
+        let mut cell_tabu = vec![vec![0; state.w()]; state.h()];
+
+        for i in 0..self.params.count {
+            let mut next = None;
+
+            for _ in 0..self.params.num_neighbors {
+                let Move { character, coord } = self.generate_move(state);
+                let mut neighbor = placement.clone();
+                neighbor.set_coord(character, coord.0, coord.1);
+                let neighbor_score = evaluator.evaluate(&neighbor);
+
+                let is_tabu = i < character_tabu[character] || i < cell_tabu[coord.0][coord.1];
+                let aspires = best_score < neighbor_score;
+
+                if (!is_tabu || aspires)
+                    && next
+                        .as_ref()
+                        .is_none_or(|(_, _, next_score)| *next_score < neighbor_score)
+                {
+                    next = Some((character, neighbor, neighbor_score));
+                }
+            }
+
+            let Some((character, neighbor, neighbor_score)) = next else {
+                continue;
+            };
+
+            let (y, x) = placement.coord(character);
+            character_tabu[character] = i + 1 + self.params.tenure;
+            cell_tabu[y][x] = i + 1 + self.params.tenure;
+            placement = neighbor;
+
+            if best_score < neighbor_score {
+                best_placement = placement.clone();
+                best_score = neighbor_score;
+            }
+        }
+
+        let final_state = state.simulate(&best_placement);
+
+        (best_placement, final_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use harness::Harness;
+    use lib::automovemaze::Agent;
+    use lib::automovemaze::{MovementRule, State};
+    use rng::Pcg32;
+
+    use super::TabuSearchAgent;
+
+    #[test]
+    fn score() {
+        let mut harness = Harness::new((0..100).collect());
+        harness.run("tabu-search", |seed| {
+            let mut rng = Pcg32::new(seed);
+            let initial_state = State::new(rng.next_u64(), 5, 5, 3, 5, MovementRule::default());
+            let (_, last_state) =
+                TabuSearchAgent::new(rng.next_u64(), 1000, 10, 1).play_game(&initial_state);
+
+            last_state.score()
+        });
+
+        println!("{}", harness);
+        assert_eq!(harness.results()[0].total_score(), 9562);
+    }
+}
@@ -0,0 +1,274 @@
+use std::io;
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+use automovemaze_lib::automovemaze::{Agent, ExhaustiveSolver, MovementRule, Placement, State};
+use automovemaze_random::RandomAgent;
+use clap::{Args, ValueEnum};
+use genetic_algorithm::GeneticAlgorithmAgent;
+use great_deluge::GreatDelugeAgent;
+use hillclimb::HillClimbAgent;
+use late_acceptance::LateAcceptanceAgent;
+use serde::{Deserialize, Serialize};
+use simulated_annealing::{
+    MultiStartSimulatedAnnealingAgent, SimulatedAnnealingAgent, DEFAULT_THREADS,
+};
+use tabu_search::TabuSearchAgent;
+
+use crate::output::{OutputArgs, Records};
+use crate::render::{self, RenderArgs};
+use crate::RunArgs;
+
+#[derive(Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AutoMoveMazeAgent {
+    Random,
+    Hillclimb,
+    SimulatedAnnealing,
+    MultiStartSimulatedAnnealing,
+    Exhaustive,
+    TabuSearch,
+    GeneticAlgorithm,
+    LateAcceptance,
+    GreatDeluge,
+}
+
+#[derive(Args, Serialize, Deserialize)]
+#[serde(
+    default = "crate::defaults",
+    rename_all = "kebab-case",
+    deny_unknown_fields
+)]
+pub struct AutoMoveMazeBoard {
+    #[arg(long, default_value_t = NonZeroUsize::new(5).unwrap())]
+    height: NonZeroUsize,
+    #[arg(long, default_value_t = NonZeroUsize::new(5).unwrap())]
+    width: NonZeroUsize,
+    #[arg(long, default_value_t = NonZeroUsize::new(3).unwrap())]
+    characters: NonZeroUsize,
+    #[arg(long, default_value_t = 5)]
+    turns: i32,
+    /// Characters may also move diagonally
+    #[arg(long)]
+    eight_directions: bool,
+    /// Characters may stay on their cell
+    #[arg(long)]
+    allow_stay: bool,
+    /// Moves off one edge come back on the opposite edge
+    #[arg(long)]
+    wrap_around: bool,
+}
+
+#[derive(Args, Serialize, Deserialize)]
+#[serde(
+    default = "crate::defaults",
+    rename_all = "kebab-case",
+    deny_unknown_fields
+)]
+pub struct AutoMoveMazeParams {
+    /// Iterations of the local search agents [default: 1000 for tabu-search, 10000 otherwise]
+    #[arg(long)]
+    count: Option<u32>,
+    #[arg(long, default_value_t = 500)]
+    temp_start: u32,
+    #[arg(long, default_value_t = 10)]
+    temp_end: u32,
+    /// Annealing chains run in parallel, which the result depends on
+    #[arg(long, default_value_t = NonZeroUsize::new(DEFAULT_THREADS).unwrap())]
+    threads: NonZeroUsize,
+    /// Neighbours sampled per tabu search iteration
+    #[arg(long, default_value_t = 10)]
+    neighbors: usize,
+    #[arg(long, default_value_t = 1)]
+    tenure: u32,
+    #[arg(long, default_value_t = NonZeroUsize::new(50).unwrap())]
+    population: NonZeroUsize,
+    #[arg(long, default_value_t = 200)]
+    generations: u32,
+    /// Wall-clock limit of the genetic algorithm in milliseconds
+    #[arg(long)]
+    time_limit: Option<u64>,
+    #[arg(long, default_value_t = NonZeroUsize::new(3).unwrap())]
+    tournament: NonZeroUsize,
+    #[arg(long, default_value_t = 0.2)]
+    mutation_rate: f64,
+    #[arg(long, default_value_t = 2)]
+    elites: usize,
+    /// Length of the late acceptance history
+    #[arg(long, default_value_t = 50)]
+    history: usize,
+    /// Water level rise per great deluge iteration
+    #[arg(long, default_value_t = 0.01)]
+    rain_speed: f64,
+}
+
+#[derive(Args)]
+pub struct AutoMoveMazeArgs {
+    #[arg(long, value_enum)]
+    agent: AutoMoveMazeAgent,
+    #[command(flatten)]
+    board: AutoMoveMazeBoard,
+    #[command(flatten)]
+    params: AutoMoveMazeParams,
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    output: OutputArgs,
+    #[command(flatten)]
+    render: RenderArgs,
+}
+
+impl AutoMoveMazeBoard {
+    // Characters move every turn, so on a single cell they need to stay or
+    // wrap around onto it
+    pub fn check(&self) -> Result<(), String> {
+        if self.height.get() == 1 && self.width.get() == 1 && !self.allow_stay && !self.wrap_around
+        {
+            return Err("the board needs a second cell to move to".to_string());
+        }
+
+        Ok(())
+    }
+
+    fn rule(&self) -> MovementRule {
+        MovementRule {
+            eight_directions: self.eight_directions,
+            allow_stay: self.allow_stay,
+            wrap_around: self.wrap_around,
+            ..MovementRule::default()
+        }
+    }
+
+    // The board of game `seed`, the same for every agent
+    pub fn initial_state(&self, seed: u64) -> State {
+        State::new(
+            rng::board_seed(seed),
+            self.height.get(),
+            self.width.get(),
+            self.characters.get(),
+            self.turns,
+            self.rule(),
+        )
+    }
+}
+
+impl AutoMoveMazeAgent {
+    pub fn name(self) -> String {
+        self.to_possible_value().unwrap().get_name().to_string()
+    }
+
+    fn build(self, params: &AutoMoveMazeParams, seed: u64) -> Box<dyn Agent> {
+        let count = params.count;
+
+        match self {
+            AutoMoveMazeAgent::Random => Box::new(RandomAgent::new(seed)),
+            AutoMoveMazeAgent::Hillclimb => {
+                Box::new(HillClimbAgent::new(seed, count.unwrap_or(10000)))
+            }
+            AutoMoveMazeAgent::SimulatedAnnealing => Box::new(SimulatedAnnealingAgent::new(
+                seed,
+                count.unwrap_or(10000),
+                params.temp_start,
+                params.temp_end,
+            )),
+            AutoMoveMazeAgent::MultiStartSimulatedAnnealing => {
+                Box::new(MultiStartSimulatedAnnealingAgent::new(
+                    seed,
+                    params.threads.get(),
+                    count.unwrap_or(10000),
+                    params.temp_start,
+                    params.temp_end,
+                ))
+            }
+            AutoMoveMazeAgent::Exhaustive => Box::new(ExhaustiveSolver::new(true)),
+            AutoMoveMazeAgent::TabuSearch => Box::new(TabuSearchAgent::new(
+                seed,
+                count.unwrap_or(1000),
+                params.neighbors,
+                params.tenure,
+            )),
+            AutoMoveMazeAgent::GeneticAlgorithm => Box::new(GeneticAlgorithmAgent::new(
+                seed,
+                params.population.get(),
+                params.generations,
+                params.time_limit.map(Duration::from_millis),
+                params.tournament.get(),
+                params.mutation_rate,
+                params.elites,
+            )),
+            AutoMoveMazeAgent::LateAcceptance => Box::new(LateAcceptanceAgent::new(
+                seed,
+                count.unwrap_or(10000),
+                params.history,
+            )),
+            AutoMoveMazeAgent::GreatDeluge => Box::new(GreatDelugeAgent::new(
+                seed,
+                count.unwrap_or(10000),
+                params.rain_speed,
+            )),
+        }
+    }
+
+    // The board and agent seeds are split from the game's seed as in the
+    // agent binaries, so the first repetition of a game can be reproduced
+    // with `<agent> --seed <seed>`. Later repetitions keep the board and
+    // draw a new seed for the agent.
+    pub fn play(
+        self,
+        board: &AutoMoveMazeBoard,
+        params: &AutoMoveMazeParams,
+        seed: u64,
+        repetition: u32,
+    ) -> (Placement, State) {
+        let state = board.initial_state(seed);
+
+        self.build(params, rng::agent_seed(seed, repetition))
+            .play_game(&state)
+    }
+}
+
+pub fn run(args: &AutoMoveMazeArgs) -> io::Result<()> {
+    args.board.check().map_err(crate::invalid_input)?;
+    let mut harness = args.run.harness();
+    let mut records = Records::default();
+    let mut last_game = None;
+    let name = args.agent.name();
+
+    records.play(
+        &mut harness,
+        "automovemaze",
+        &name,
+        &name,
+        &args.board,
+        &args.params,
+        |seed, repetition| {
+            let (placement, state) = args.agent.play(&args.board, &args.params, seed, repetition);
+            let result = (state.score(), state.turn(), None);
+            last_game = Some((seed, placement, state));
+
+            result
+        },
+    );
+
+    if let (true, Some((seed, placement, _))) = (args.render.is_requested(), &last_game) {
+        let initial_state = args.board.initial_state(*seed);
+        args.render.write(
+            &name,
+            &render::automovemaze_frames(&initial_state, placement),
+        )?;
+    }
+
+    let preamble = match (harness.seeds(), last_game) {
+        ([seed], Some((_, placement, state))) => {
+            let threads = match args.agent {
+                AutoMoveMazeAgent::MultiStartSimulatedAnnealing => {
+                    format!("threads:\t{}\n", args.params.threads)
+                }
+                _ => String::new(),
+            };
+            format!("seed:\t{}\n{}{:?}\n{:?}\n", seed, threads, placement, state)
+        }
+        _ => String::new(),
+    };
+    args.output.write(&preamble, &harness, &records)
+}
@@ -0,0 +1,378 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::maze::{MazeAgent, MazeParams};
+
+// Sources are built in, so a bundle always matches the agents of this binary
+const RNG: &str = include_str!("../../rng/src/lib.rs");
+const MAZE_LIB: &str = include_str!("../../maze/lib/src/lib.rs");
+
+#[derive(Args)]
+pub struct BundleArgs {
+    #[arg(long, value_enum)]
+    agent: MazeAgent,
+    #[command(flatten)]
+    params: MazeParams,
+    /// Seed of the agent's randomness
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Write the submission to this file instead of standard output
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+impl MazeAgent {
+    // Module name and source of the agent crate's library
+    fn source(self) -> (&'static str, &'static str) {
+        match self {
+            MazeAgent::Random => ("maze_random", include_str!("../../maze/random/src/lib.rs")),
+            MazeAgent::Greedy => ("greedy", include_str!("../../maze/greedy/src/lib.rs")),
+            MazeAgent::BeamSearch => (
+                "beam_search",
+                include_str!("../../maze/beam-search/src/lib.rs"),
+            ),
+            MazeAgent::ChokudaiSearch => (
+                "chokudai_search",
+                include_str!("../../maze/chokudai-search/src/lib.rs"),
+            ),
+        }
+    }
+}
+
+pub fn bundle(args: &BundleArgs) -> io::Result<()> {
+    let source = bundle_source(args.agent, &args.params, args.seed)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    match &args.output {
+        Some(path) => fs::write(path, source),
+        None => io::stdout().lock().write_all(source.as_bytes()),
+    }
+}
+
+// One source file playing the agent in contest mode, with the rng crate, the
+// maze lib and the agent crate as modules. Tests and anything behind a cargo
+// feature are left out, so it builds with the standard library alone.
+pub fn bundle_source(agent: MazeAgent, params: &MazeParams, seed: u64) -> Result<String, String> {
+    agent.check(params)?;
+    let (module, agent_source) = agent.source();
+    let mut out = format!(
+        "// Bundled by `cli bundle --agent {}` from rng, maze/lib and the agent crate\n\
+         #![allow(dead_code)]\n\
+         \n\
+         use std::io::Read;\n",
+        agent.name()
+    );
+
+    for (name, source) in [("rng", RNG), ("lib", MAZE_LIB), (module, agent_source)] {
+        let source = strip_cfg(source)?;
+        if source.contains("rand::") {
+            return Err(format!(
+                "{} needs the rand crate, which a submission cannot use",
+                name
+            ));
+        }
+
+        out.push_str(&format!("\npub mod {} {{\n", name));
+        out.push_str(&indent(&crate_paths(source.trim_end())));
+        out.push_str("}\n");
+    }
+
+    out.push_str(&format!(
+        "\n\
+         fn main() {{\n    \
+             let mut input = String::new();\n    \
+             std::io::stdin().read_to_string(&mut input).unwrap();\n    \
+             let state = lib::contest::read_input(&input).unwrap();\n    \
+             let mut agent = {};\n    \
+             let actions = lib::contest::solve(&mut agent, &state);\n    \
+             print!(\"{{}}\", lib::contest::write_output(&actions));\n\
+         }}\n",
+        agent.constructor(params, seed)
+    ));
+
+    Ok(out)
+}
+
+// Imports from the other bundled crates now start at the crate root
+fn crate_paths(source: &str) -> String {
+    source
+        .lines()
+        .map(|line| {
+            let code = line.trim_start();
+            let indent = &line[..line.len() - code.len()];
+
+            match ["use lib::", "use rng::"]
+                .iter()
+                .find(|p| code.starts_with(*p))
+            {
+                Some(_) => format!("{}use crate::{}\n", indent, &code[4..]),
+                None => format!("{}\n", line),
+            }
+        })
+        .collect()
+}
+
+fn indent(source: &str) -> String {
+    source
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                "\n".to_string()
+            } else {
+                format!("    {}\n", line)
+            }
+        })
+        .collect()
+}
+
+// Drops the items under #[cfg(test)] or #[cfg(feature = ...)] and every
+// #[cfg_attr(feature = ..., ...)], as if built without tests or features.
+// Works line by line on rustfmt-formatted source.
+fn strip_cfg(source: &str) -> Result<String, String> {
+    let lines = source.lines().collect::<Vec<_>>();
+    let mut out = String::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        if !lines[i].trim_start().starts_with("#[cfg") {
+            out.push_str(lines[i]);
+            out.push('\n');
+            i += 1;
+            continue;
+        }
+
+        let end = attribute_end(&lines, i);
+        let attribute = lines[i..end].iter().map(|l| l.trim()).collect::<String>();
+        let supported = match attribute.strip_prefix("#[cfg_attr(") {
+            Some(condition) => condition.starts_with("feature"),
+            None => ["#[cfg(test)]", "#[cfg(feature", "#[cfg(all(test, feature"]
+                .iter()
+                .any(|prefix| attribute.starts_with(prefix)),
+        };
+        if !supported {
+            return Err(format!("line {}: unsupported {}", i + 1, attribute));
+        }
+
+        i = end;
+        if attribute.starts_with("#[cfg(") {
+            while lines
+                .get(i)
+                .is_some_and(|l| l.trim_start().starts_with("#["))
+            {
+                i = attribute_end(&lines, i);
+            }
+            i = item_end(&lines, i);
+
+            // Along with the blank line separating it from the item before
+            if out.ends_with("\n\n") {
+                out.pop();
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+// Index of the line after the attribute starting at line `i`
+fn attribute_end(lines: &[&str], i: usize) -> usize {
+    let mut scanner = Scanner::default();
+    let mut j = i;
+
+    while j < lines.len() {
+        scanner.scan(lines[j]);
+        j += 1;
+        if scanner.depth == 0 {
+            break;
+        }
+    }
+
+    j
+}
+
+// Index of the line after the item starting at line `i`: the braces it
+// opens are closed and it ends with `}` or `;`, or `,` for a struct field
+fn item_end(lines: &[&str], i: usize) -> usize {
+    let mut scanner = Scanner::default();
+    let mut j = i;
+
+    while j < lines.len() {
+        scanner.scan(lines[j]);
+        j += 1;
+
+        let line = lines[j - 1].trim_end();
+        if scanner.depth == 0 && !scanner.in_string && line.ends_with(['}', ';', ',']) {
+            break;
+        }
+    }
+
+    j
+}
+
+// Bracket depth outside of strings, characters and comments
+#[derive(Default)]
+struct Scanner {
+    depth: i32,
+    in_string: bool,
+    // Number of # closing the raw string being scanned, if any
+    raw_hashes: Option<usize>,
+}
+
+impl Scanner {
+    fn scan(&mut self, line: &str) {
+        let chars = line.chars().collect::<Vec<_>>();
+        let mut k = 0;
+
+        while k < chars.len() {
+            let c = chars[k];
+
+            if self.in_string {
+                match self.raw_hashes {
+                    Some(hashes) => {
+                        if c == '"'
+                            && chars[k + 1..].iter().take_while(|&&c| c == '#').count() >= hashes
+                        {
+                            self.in_string = false;
+                            self.raw_hashes = None;
+                            k += hashes;
+                        }
+                    }
+                    None => match c {
+                        '\\' => k += 1,
+                        '"' => self.in_string = false,
+                        _ => {}
+                    },
+                }
+            } else {
+                match c {
+                    '/' if chars.get(k + 1) == Some(&'/') => return,
+                    '"' => self.in_string = true,
+                    'r' if matches!(chars.get(k + 1), Some('"' | '#'))
+                        && (k == 0 || !chars[k - 1].is_alphanumeric() && chars[k - 1] != '_') =>
+                    {
+                        let hashes = chars[k + 1..].iter().take_while(|&&c| c == '#').count();
+                        if chars.get(k + 1 + hashes) == Some(&'"') {
+                            self.in_string = true;
+                            self.raw_hashes = Some(hashes);
+                            k += 1 + hashes;
+                        }
+                    }
+                    // A character literal, not a lifetime
+                    '\'' if chars.get(k + 1) == Some(&'\\') => {
+                        k += 2;
+                        while k < chars.len() && chars[k] != '\'' {
+                            k += 1;
+                        }
+                    }
+                    '\'' if chars.get(k + 2) == Some(&'\'') => k += 2,
+                    '{' | '(' | '[' => self.depth += 1,
+                    '}' | ')' | ']' => self.depth -= 1,
+                    _ => {}
+                }
+            }
+
+            k += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+    use std::{env, fs, io::Write, process};
+
+    use maze_lib::contest;
+    use maze_lib::maze::GameState;
+
+    use super::{bundle_source, strip_cfg};
+    use crate::defaults;
+    use crate::maze::{MazeAgent, MazeParams};
+
+    #[test]
+    fn strips_tests_and_features() {
+        let source = "use a;\n\
+                      #[cfg(feature = \"x\")]\n\
+                      use b::{\n    c,\n};\n\
+                      #[derive(Clone)]\n\
+                      #[cfg_attr(\n    feature = \"x\",\n    derive(Debug)\n)]\n\
+                      struct S {\n    #[cfg(feature = \"x\")]\n    x: u8,\n}\n\
+                      #[cfg(test)]\n\
+                      mod tests {\n    const T: &str = \"}\";\n    const U: char = '{';\n    const V: &str = r#\"{\"#;\n}\n\
+                      fn f() {}\n";
+
+        assert_eq!(
+            strip_cfg(source).unwrap(),
+            "use a;\n#[derive(Clone)]\nstruct S {\n}\nfn f() {}\n"
+        );
+        assert!(strip_cfg("#[cfg(unix)]\nfn f() {}\n").is_err());
+    }
+
+    // Compiles each bundle with rustc and checks it answers like the agent
+    #[test]
+    fn bundles_compile_and_match() {
+        let params: MazeParams = defaults();
+        let dir = env::temp_dir().join(format!("cli-bundle-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        for agent in [
+            MazeAgent::Random,
+            MazeAgent::Greedy,
+            MazeAgent::BeamSearch,
+            MazeAgent::ChokudaiSearch,
+        ] {
+            let source = dir.join(format!("{}.rs", agent.name()));
+            let binary = dir.join(agent.name());
+            fs::write(&source, bundle_source(agent, &params, 5).unwrap()).unwrap();
+            compile(&source, &binary);
+
+            for seed in 0..3 {
+                let state = GameState::new(6, 7, 10, seed);
+                let expected = contest::solve(agent.build(&params, 5).as_mut(), &state);
+
+                assert_eq!(
+                    run(&binary, &contest::write_input(&state)),
+                    contest::write_output(&expected),
+                    "{} on seed {}",
+                    agent.name(),
+                    seed
+                );
+            }
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn compile(source: &Path, binary: &Path) {
+        let output = Command::new(env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string()))
+            .args(["--edition", "2021", "-O", "-o"])
+            .arg(binary)
+            .arg(source)
+            .output()
+            .unwrap();
+
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn run(binary: &Path, input: &str) -> String {
+        let mut child = Command::new(binary)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+
+        String::from_utf8(child.wait_with_output().unwrap().stdout).unwrap()
+    }
+}
@@ -0,0 +1,231 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use std::{error, fs, thread};
+
+use clap::Args;
+use maze_lib::contest;
+use maze_lib::maze::{Action, GameState};
+use maze_lib::GameStateBase;
+
+use crate::maze::{MazeAgent, MazeBoard, MazeParams};
+
+#[derive(Args)]
+pub struct SolveArgs {
+    #[arg(long, value_enum)]
+    agent: MazeAgent,
+    #[command(flatten)]
+    params: MazeParams,
+    /// Seed of the agent's randomness
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+#[derive(Args)]
+pub struct ScoreArgs {
+    input: PathBuf,
+    output: PathBuf,
+}
+
+#[derive(Args)]
+pub struct GenerateArgs {
+    #[command(flatten)]
+    board: MazeBoard,
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+#[derive(Args)]
+pub struct JudgeArgs {
+    #[command(flatten)]
+    board: MazeBoard,
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Time the agent has to answer each turn, in milliseconds
+    #[arg(long, default_value_t = 1000)]
+    time_limit: u64,
+    /// The agent's command line, e.g. `-- cli interact --agent greedy`
+    #[arg(last = true, required = true)]
+    command: Vec<String>,
+}
+
+// Reads a problem from standard input and prints the agent's answer
+pub fn solve(args: &SolveArgs) -> io::Result<()> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    let state = contest::read_input(&input).map_err(invalid_data)?;
+    args.agent
+        .check(&args.params)
+        .map_err(crate::invalid_input)?;
+
+    let mut agent = args.agent.build(&args.params, args.seed);
+    let actions = contest::solve(agent.as_mut(), &state);
+
+    let mut out = io::stdout().lock();
+    out.write_all(contest::write_output(&actions).as_bytes())?;
+    out.flush()
+}
+
+// Scores an answer file against its input like the judge would
+pub fn score(args: &ScoreArgs) -> io::Result<()> {
+    let state = fs::read_to_string(&args.input)
+        .and_then(|input| contest::read_input(&input).map_err(invalid_data))
+        .map_err(|err| in_file(&args.input, err))?;
+    let actions = fs::read_to_string(&args.output)
+        .and_then(|output| contest::read_output(&output).map_err(invalid_data))
+        .map_err(|err| in_file(&args.output, err))?;
+    let score = contest::score(&state, &actions).map_err(invalid_data)?;
+
+    println!("score:\t{}", score);
+
+    Ok(())
+}
+
+// Prints the problem input of the board generated from the seed, the same
+// board every agent plays with `cli maze --seed <seed>`
+pub fn generate(args: &GenerateArgs) -> io::Result<()> {
+    print!(
+        "{}",
+        contest::write_input(&args.board.initial_state(args.seed))
+    );
+
+    Ok(())
+}
+
+// Plays the agent side of the interactive protocol over standard input and
+// output
+pub fn interact(args: &SolveArgs) -> io::Result<()> {
+    args.agent
+        .check(&args.params)
+        .map_err(crate::invalid_input)?;
+    let mut agent = args.agent.build(&args.params, args.seed);
+
+    contest::interact(agent.as_mut(), io::stdin().lock(), io::stdout().lock())?;
+
+    Ok(())
+}
+
+// Runs an agent process against the board generated from the seed and
+// reports its score. The agent fails on a late, unreadable or illegal
+// answer, or when it exits early. After the game it has the time limit to
+// exit before it is killed.
+pub fn judge(args: &JudgeArgs) -> io::Result<()> {
+    args.board.check().map_err(crate::invalid_input)?;
+    let mut child = Command::new(&args.command[0])
+        .args(&args.command[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|err| io::Error::new(err.kind(), format!("{}: {}", args.command[0], err)))?;
+
+    let state = args.board.initial_state(args.seed);
+    let time_limit = Duration::from_millis(args.time_limit);
+    let result = run_judge(&mut child, state, time_limit);
+    if result.is_err() {
+        let _ = child.kill();
+    }
+    drop(child.stdin.take());
+    wait_or_kill(&mut child, time_limit)?;
+
+    let (state, slowest) = result?;
+    println!("score:\t{}", state.score);
+    println!("slowest turn:\t{:.3} ms", slowest.as_secs_f64() * 1000.0);
+
+    Ok(())
+}
+
+// Returns the final state and the longest time the agent took for a turn
+fn run_judge(
+    child: &mut Child,
+    mut state: GameState,
+    time_limit: Duration,
+) -> io::Result<(GameState, Duration)> {
+    let mut stdin = child.stdin.take().unwrap();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+
+    // Lines are read on another thread so a silent agent can time out
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in stdout.lines() {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    // Writing fails once the agent has closed its input, usually by exiting
+    let exited = |turn| move |_| judge_error(turn, "the agent exited");
+    stdin
+        .write_all(contest::write_input(&state).as_bytes())
+        .map_err(exited(1))?;
+    let mut slowest = Duration::ZERO;
+
+    while !state.is_game_over() {
+        let turn = state.turn() + 1;
+        let started = Instant::now();
+        stdin
+            .write_all(contest::write_turn(&state).as_bytes())
+            .and_then(|_| stdin.flush())
+            .map_err(exited(turn))?;
+
+        let line = match receiver.recv_timeout(time_limit) {
+            Ok(line) => line.map_err(exited(turn))?,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                return Err(judge_error(turn, "no answer within the time limit"));
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err(judge_error(turn, "the agent exited"));
+            }
+        };
+        slowest = slowest.max(started.elapsed());
+
+        let action = line
+            .trim()
+            .parse::<Action>()
+            .map_err(|err| judge_error(turn, &err.to_string()))?;
+        if !state.legal_actions().contains(action) {
+            return Err(judge_error(
+                turn,
+                &format!("{} moves off the board", action),
+            ));
+        }
+        state.advance(action);
+    }
+
+    // The game is over either way, so an agent that quits without reading
+    // the end line still gets its score
+    let _ = writeln!(stdin, "end {}", state.score);
+
+    Ok((state, slowest))
+}
+
+fn wait_or_kill(child: &mut Child, time_limit: Duration) -> io::Result<()> {
+    let started = Instant::now();
+    while child.try_wait()?.is_none() {
+        if started.elapsed() >= time_limit {
+            let _ = child.kill();
+            break;
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+    child.wait()?;
+
+    Ok(())
+}
+
+fn judge_error(turn: i32, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("turn {}: {}", turn, message),
+    )
+}
+
+fn invalid_data<E: Into<Box<dyn error::Error + Send + Sync>>>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+fn in_file(path: &Path, err: io::Error) -> io::Error {
+    io::Error::new(err.kind(), format!("{}: {}", path.display(), err))
+}
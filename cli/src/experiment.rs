@@ -0,0 +1,339 @@
+use clap::Args;
+use harness::Harness;
+use serde::Deserialize;
+
+use crate::automovemaze::{AutoMoveMazeAgent, AutoMoveMazeBoard, AutoMoveMazeParams};
+use crate::maze::{MazeAgent, MazeBoard, MazeParams};
+use crate::output::Records;
+
+// An experiment file plays every listed agent on the same boards, one per
+// seed, e.g.
+//
+//   game = "maze"
+//
+//   [board]
+//   height = 10
+//   width = 10
+//   turns = 20
+//
+//   [seeds]
+//   start = 0
+//   end = 100
+//   repetitions = 1
+//
+//   [[agents]]
+//   agent = "beam-search"
+//   name = "beam-search-w4"
+//   params = { beam-width = 4 }
+//
+// Keys are the long flags of the matching subcommand and anything left out
+// takes the same default as on the command line. A top-level
+// `reference = "<name>"` adds each agent's mean gap to that agent to the
+// table, e.g. to the exhaustive optimum.
+#[derive(Deserialize)]
+#[serde(tag = "game", rename_all = "kebab-case")]
+pub enum Experiment {
+    Maze(GameExperiment<MazeBoard, MazeAgent, MazeParams>),
+    Automovemaze(GameExperiment<AutoMoveMazeBoard, AutoMoveMazeAgent, AutoMoveMazeParams>),
+}
+
+#[derive(Deserialize)]
+#[serde(
+    deny_unknown_fields,
+    bound(
+        deserialize = "B: Args + Deserialize<'de>, A: Deserialize<'de>, P: Args + Deserialize<'de>"
+    )
+)]
+pub struct GameExperiment<B, A, P> {
+    #[serde(default = "crate::defaults")]
+    board: B,
+    seeds: SeedRange,
+    agents: Vec<AgentConfig<A, P>>,
+    reference: Option<String>,
+}
+
+// Seeds start..end, each played `repetitions` times in a row
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeedRange {
+    start: u64,
+    end: u64,
+    #[serde(default = "one")]
+    repetitions: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(
+    deny_unknown_fields,
+    bound(deserialize = "A: Deserialize<'de>, P: Args + Deserialize<'de>")
+)]
+pub struct AgentConfig<A, P> {
+    agent: A,
+    // Label in the results, needed when one agent is listed with several
+    // parameter sets. Defaults to the agent name.
+    name: Option<String>,
+    #[serde(default = "crate::defaults")]
+    params: P,
+}
+
+fn one() -> u32 {
+    1
+}
+
+impl SeedRange {
+    fn harness(&self) -> Harness {
+        let seeds = (self.start..self.end)
+            .flat_map(|seed| (0..self.repetitions).map(move |_| seed))
+            .collect();
+
+        Harness::new(seeds)
+    }
+}
+
+impl Experiment {
+    pub fn run(&self) -> Result<(Harness, Records), String> {
+        let mut records = Records::default();
+
+        let (mut harness, reference) = match self {
+            Experiment::Maze(experiment) => {
+                experiment.board.check()?;
+                let mut harness = experiment.seeds.harness();
+
+                for config in &experiment.agents {
+                    config.agent.check(&config.params)?;
+                    let agent = config.agent.name();
+                    let name = config.name.as_ref().unwrap_or(&agent);
+                    records.play(
+                        &mut harness,
+                        "maze",
+                        &agent,
+                        name,
+                        &experiment.board,
+                        &config.params,
+                        |seed, repetition| {
+                            let (_, state, stats) = config.agent.play(
+                                &experiment.board,
+                                &config.params,
+                                seed,
+                                repetition,
+                            );
+
+                            (state.score, state.turn(), stats)
+                        },
+                    );
+                }
+
+                (harness, &experiment.reference)
+            }
+            Experiment::Automovemaze(experiment) => {
+                experiment.board.check()?;
+                let mut harness = experiment.seeds.harness();
+
+                for config in &experiment.agents {
+                    let agent = config.agent.name();
+                    let name = config.name.as_ref().unwrap_or(&agent);
+                    records.play(
+                        &mut harness,
+                        "automovemaze",
+                        &agent,
+                        name,
+                        &experiment.board,
+                        &config.params,
+                        |seed, repetition| {
+                            let (_, state) = config.agent.play(
+                                &experiment.board,
+                                &config.params,
+                                seed,
+                                repetition,
+                            );
+
+                            (state.score(), state.turn(), None)
+                        },
+                    );
+                }
+
+                (harness, &experiment.reference)
+            }
+        };
+        if let Some(reference) = reference {
+            harness.set_reference(reference)?;
+        }
+
+        Ok((harness, records))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::Experiment;
+
+    #[test]
+    fn checked_in_experiments() {
+        for entry in fs::read_dir("experiments").unwrap() {
+            let path = entry.unwrap().path();
+            let text = fs::read_to_string(&path).unwrap();
+
+            if let Err(err) = toml::from_str::<Experiment>(&text) {
+                panic!("{}: {}", path.display(), err);
+            }
+        }
+    }
+
+    #[test]
+    fn repetitions() {
+        let experiment: Experiment = toml::from_str(
+            r#"
+            game = "automovemaze"
+
+            [seeds]
+            start = 0
+            end = 5
+            repetitions = 3
+
+            [[agents]]
+            agent = "random"
+
+            [[agents]]
+            agent = "hillclimb"
+            name = "hillclimb-100"
+            params = { count = 100 }
+            "#,
+        )
+        .unwrap();
+        let (harness, _) = experiment.run().unwrap();
+
+        assert_eq!(harness.seeds().len(), 15);
+        assert_eq!(harness.results()[1].name, "hillclimb-100");
+
+        // Repetitions share the board but not the agent's randomness
+        let scores = harness.results()[0]
+            .games
+            .iter()
+            .map(|game| game.score)
+            .collect::<Vec<_>>();
+        assert!(scores.chunks(3).any(|chunk| chunk[0] != chunk[1]));
+    }
+
+    // Every agent is given the board of the seed, so none can beat the
+    // exhaustive optimum
+    #[test]
+    fn shared_boards() {
+        let text = fs::read_to_string("experiments/automovemaze-local-search.toml").unwrap();
+        let Experiment::Automovemaze(mut experiment) = toml::from_str(&text).unwrap() else {
+            panic!("not an automovemaze experiment");
+        };
+        experiment.seeds.end = 30;
+
+        for seed in experiment.seeds.start..experiment.seeds.end {
+            let initial_state = experiment.board.initial_state(seed);
+            let scores = experiment
+                .agents
+                .iter()
+                .map(|config| {
+                    let (placement, state) =
+                        config
+                            .agent
+                            .play(&experiment.board, &config.params, seed, 0);
+                    assert_eq!(
+                        initial_state.evaluate(&placement),
+                        state.score(),
+                        "{} on seed {}",
+                        config.agent.name(),
+                        seed
+                    );
+
+                    state.score()
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(experiment.agents[0].agent.name(), "exhaustive");
+            assert!(
+                scores.iter().all(|&score| score <= scores[0]),
+                "seed {}",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_reference() {
+        let experiment: Experiment = toml::from_str(
+            r#"
+            game = "maze"
+            seeds = { start = 0, end = 1 }
+            agents = [{ agent = "greedy" }]
+            reference = "beam-search"
+            "#,
+        )
+        .unwrap();
+
+        assert!(experiment.run().is_err());
+    }
+
+    #[test]
+    fn unknown_parameter() {
+        let experiment = toml::from_str::<Experiment>(
+            r#"
+            game = "maze"
+            seeds = { start = 0, end = 1 }
+            agents = [{ agent = "beam-search", params = { beam-widht = 4 } }]
+            "#,
+        );
+
+        assert!(experiment.is_err());
+    }
+
+    #[test]
+    fn degenerate_sizes() {
+        let experiment = toml::from_str::<Experiment>(
+            r#"
+            game = "maze"
+            seeds = { start = 0, end = 1 }
+            agents = [{ agent = "beam-search", params = { beam-width = 0 } }]
+            "#,
+        );
+        assert!(experiment.is_err());
+
+        for text in [
+            r#"
+            game = "maze"
+            board = { height = 1, width = 1 }
+            seeds = { start = 0, end = 1 }
+            agents = [{ agent = "greedy" }]
+            "#,
+            r#"
+            game = "maze"
+            seeds = { start = 0, end = 1 }
+            agents = [{ agent = "chokudai-search", params = { beam-depth = 1 } }]
+            "#,
+            r#"
+            game = "automovemaze"
+            board = { height = 1, width = 1 }
+            seeds = { start = 0, end = 1 }
+            agents = [{ agent = "random" }]
+            "#,
+        ] {
+            let experiment = toml::from_str::<Experiment>(text).unwrap();
+
+            assert!(experiment.run().is_err());
+        }
+    }
+
+    #[test]
+    fn empty_population() {
+        for params in ["population = 0", "tournament = 0"] {
+            let experiment = toml::from_str::<Experiment>(&format!(
+                r#"
+                game = "automovemaze"
+                seeds = {{ start = 0, end = 1 }}
+                agents = [{{ agent = "genetic-algorithm", params = {{ {} }} }}]
+                "#,
+                params
+            ));
+
+            assert!(experiment.is_err());
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::{fs, io, path::PathBuf, process};
+
+use clap::{Args, Command, Parser, Subcommand};
+use harness::Harness;
+
+use experiment::Experiment;
+use output::OutputArgs;
+
+mod automovemaze;
+mod bundle;
+mod contest;
+mod experiment;
+mod maze;
+mod output;
+mod render;
+
+// Runs any agent of either game from one binary, e.g.
+//   cli maze --agent beam-search --beam-width 4 --games 100 --seed 0
+//   cli automovemaze --agent simulated-annealing --count 20000 --seed 42
+//   cli replay game.txt --html game.html
+//   cli experiment experiments/maze-beam-width.toml --format csv --output results.csv
+//   cli solve --agent beam-search < input.txt > output.txt
+//   cli judge --seed 3 --time-limit 50 -- cli interact --agent greedy
+//   cli bundle --agent chokudai-search --beam-width 8 --output submission.rs
+//   cli generate --seed 3 | cli tree --agent beam-search --max-nodes 200 > tree.dot
+#[derive(Parser)]
+#[command(about = "Play the maze and automovemaze agents")]
+struct Cli {
+    #[command(subcommand)]
+    command: CliCommand,
+}
+
+#[derive(Subcommand)]
+enum CliCommand {
+    /// Single player collecting points on a grid
+    Maze(maze::MazeArgs),
+    /// Place characters that then move greedily on their own
+    Automovemaze(automovemaze::AutoMoveMazeArgs),
+    /// Run every agent of a TOML experiment file on its seeds
+    Experiment(ExperimentArgs),
+    /// Replay a saved maze game, checking the score after every action
+    Replay(maze::ReplayArgs),
+    /// Read a maze problem from standard input and print the agent's actions
+    Solve(contest::SolveArgs),
+    /// Score an output file against its maze problem input
+    Score(contest::ScoreArgs),
+    /// Print the maze problem input for a seed
+    Generate(contest::GenerateArgs),
+    /// Play a maze game over the interactive protocol on standard input and output
+    Interact(contest::SolveArgs),
+    /// Run an agent process through an interactive maze game and score it
+    Judge(contest::JudgeArgs),
+    /// Print a single-file contest submission for a maze agent
+    Bundle(bundle::BundleArgs),
+    /// Print the tree a maze search agent explores from a position, for Graphviz
+    Tree(maze::TreeArgs),
+}
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// Seed of the first game, random when omitted
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Number of games, played on consecutive seeds starting at --seed and
+    /// wrapping around after the largest seed
+    #[arg(long, default_value_t = 1)]
+    games: u64,
+}
+
+#[derive(Args)]
+struct ExperimentArgs {
+    path: PathBuf,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+impl RunArgs {
+    pub fn harness(&self) -> Harness {
+        let seed = self.seed.unwrap_or_else(rng::random_seed);
+
+        Harness::new(
+            (0..self.games)
+                .map(|game| seed.wrapping_add(game))
+                .collect(),
+        )
+    }
+}
+
+// Flags that clap accepts one by one but that do not work together
+pub fn invalid_input(err: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, err)
+}
+
+// Values of a group of flags when none are given, so experiment files fall
+// back to the same defaults as the command line
+pub fn defaults<T: Args>() -> T {
+    let command = T::augment_args(Command::new("defaults"));
+
+    T::from_arg_matches(&command.get_matches_from(["defaults"])).unwrap()
+}
+
+// Plays every seed of the harness. A seed listed several times in a row is
+// passed with the number of times it has already been played, so repeated
+// games can vary the agent's randomness.
+pub fn play_games<F>(harness: &mut Harness, name: &str, mut play_game: F)
+where
+    F: FnMut(u64, u32) -> i32,
+{
+    let mut previous = None;
+    let mut repetition = 0;
+
+    harness.run(name, |seed| {
+        repetition = if previous == Some(seed) {
+            repetition + 1
+        } else {
+            0
+        };
+        previous = Some(seed);
+
+        play_game(seed, repetition)
+    });
+}
+
+fn run_experiment(args: &ExperimentArgs) -> io::Result<()> {
+    let experiment: Experiment = fs::read_to_string(&args.path)
+        .and_then(|text| toml::from_str(&text).map_err(io::Error::other))
+        .map_err(|err| io::Error::new(err.kind(), format!("{}: {}", args.path.display(), err)))?;
+    let (harness, records) = experiment
+        .run()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    args.output.write("", &harness, &records)
+}
+
+fn main() {
+    let result = match Cli::parse().command {
+        CliCommand::Maze(args) => maze::run(&args),
+        CliCommand::Automovemaze(args) => automovemaze::run(&args),
+        CliCommand::Experiment(args) => run_experiment(&args),
+        CliCommand::Replay(args) => maze::replay(&args),
+        CliCommand::Solve(args) => contest::solve(&args),
+        CliCommand::Score(args) => contest::score(&args),
+        CliCommand::Generate(args) => contest::generate(&args),
+        CliCommand::Interact(args) => contest::interact(&args),
+        CliCommand::Judge(args) => contest::judge(&args),
+        CliCommand::Bundle(args) => bundle::bundle(&args),
+        CliCommand::Tree(args) => maze::tree(&args),
+    };
+
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::RunArgs;
+
+    #[test]
+    fn verify_cli() {
+        super::Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn seeds_wrap_around() {
+        let args = RunArgs {
+            seed: Some(u64::MAX - 1),
+            games: 3,
+        };
+
+        assert_eq!(args.harness().seeds(), [u64::MAX - 1, u64::MAX, 0]);
+    }
+}
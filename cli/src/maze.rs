@@ -0,0 +1,323 @@
+use std::io::{Read, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use beam_search::BeamSearchAgent;
+use chokudai_search::ChokudaiSearchAgent;
+use clap::{Args, ValueEnum};
+use greedy::GreedyAgent;
+use maze_lib::contest;
+use maze_lib::maze::{Action, GameRecord, GameState};
+use maze_lib::stats::{self, SearchStats};
+use maze_lib::Agent;
+use maze_random::RandomAgent;
+use serde::{Deserialize, Serialize};
+
+use crate::output::{OutputArgs, Records};
+use crate::render::{Frame, RenderArgs};
+use crate::RunArgs;
+
+#[derive(Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MazeAgent {
+    Random,
+    Greedy,
+    BeamSearch,
+    ChokudaiSearch,
+}
+
+#[derive(Args, Serialize, Deserialize)]
+#[serde(
+    default = "crate::defaults",
+    rename_all = "kebab-case",
+    deny_unknown_fields
+)]
+pub struct MazeBoard {
+    #[arg(long, default_value_t = NonZeroUsize::new(3).unwrap())]
+    height: NonZeroUsize,
+    #[arg(long, default_value_t = NonZeroUsize::new(3).unwrap())]
+    width: NonZeroUsize,
+    #[arg(long, default_value_t = 4)]
+    turns: i32,
+}
+
+#[derive(Args, Serialize, Deserialize)]
+#[serde(
+    default = "crate::defaults",
+    rename_all = "kebab-case",
+    deny_unknown_fields
+)]
+pub struct MazeParams {
+    #[arg(long, default_value_t = NonZeroUsize::new(2).unwrap())]
+    beam_width: NonZeroUsize,
+    #[arg(long, default_value_t = NonZeroUsize::new(4).unwrap())]
+    beam_depth: NonZeroUsize,
+    /// Number of beams per Chokudai search
+    #[arg(long, default_value_t = NonZeroUsize::new(2).unwrap())]
+    beam_count: NonZeroUsize,
+}
+
+#[derive(Args)]
+pub struct MazeArgs {
+    #[arg(long, value_enum)]
+    agent: MazeAgent,
+    #[command(flatten)]
+    board: MazeBoard,
+    #[command(flatten)]
+    params: MazeParams,
+    #[command(flatten)]
+    run: RunArgs,
+    #[command(flatten)]
+    output: OutputArgs,
+    /// Save the record of the last game played, for `cli replay`
+    #[arg(long)]
+    record: Option<PathBuf>,
+    #[command(flatten)]
+    render: RenderArgs,
+}
+
+impl MazeBoard {
+    // The player moves every turn, so needs a second cell to move to
+    pub fn check(&self) -> Result<(), String> {
+        if self.height.get() == 1 && self.width.get() == 1 {
+            return Err("the board needs a second cell to move to".to_string());
+        }
+
+        Ok(())
+    }
+
+    // The board of game `seed`, the same for every agent
+    pub fn initial_state(&self, seed: u64) -> GameState {
+        GameState::new(
+            self.height.get(),
+            self.width.get(),
+            self.turns,
+            rng::board_seed(seed),
+        )
+    }
+}
+
+impl MazeAgent {
+    pub fn name(self) -> String {
+        self.to_possible_value().unwrap().get_name().to_string()
+    }
+
+    // Every search flag is at least 1, but Chokudai search only moves with
+    // a depth of 2
+    pub fn check(self, params: &MazeParams) -> Result<(), String> {
+        if let MazeAgent::ChokudaiSearch = self {
+            if params.beam_depth.get() < 2 {
+                return Err(format!("{} needs a beam depth of at least 2", self.name()));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn build(self, params: &MazeParams, seed: u64) -> Box<dyn Agent<GameState, Action>> {
+        match self {
+            MazeAgent::Random => Box::new(RandomAgent::new(seed)),
+            MazeAgent::Greedy => Box::new(GreedyAgent::new()),
+            MazeAgent::BeamSearch => Box::new(BeamSearchAgent::new(
+                params.beam_width.get(),
+                params.beam_depth.get(),
+            )),
+            MazeAgent::ChokudaiSearch => Box::new(ChokudaiSearchAgent::new(
+                params.beam_width.get(),
+                params.beam_depth.get(),
+                params.beam_count.get(),
+            )),
+        }
+    }
+
+    // The Rust expression that builds the same agent in a bundled
+    // submission, where each agent crate is a module of the same name
+    pub fn constructor(self, params: &MazeParams, seed: u64) -> String {
+        match self {
+            MazeAgent::Random => format!("maze_random::RandomAgent::new({})", seed),
+            MazeAgent::Greedy => "greedy::GreedyAgent::new()".to_string(),
+            MazeAgent::BeamSearch => format!(
+                "beam_search::BeamSearchAgent::new({}, {})",
+                params.beam_width.get(),
+                params.beam_depth.get()
+            ),
+            MazeAgent::ChokudaiSearch => format!(
+                "chokudai_search::ChokudaiSearchAgent::new({}, {}, {})",
+                params.beam_width.get(),
+                params.beam_depth.get(),
+                params.beam_count.get()
+            ),
+        }
+    }
+
+    // The board and agent seeds are split from the game's seed as in the
+    // agent binaries, so the first repetition of a game can be reproduced
+    // with `<agent> --seed <seed>`. Later repetitions keep the board and
+    // draw a new seed for the agent.
+    pub fn play(
+        self,
+        board: &MazeBoard,
+        params: &MazeParams,
+        seed: u64,
+        repetition: u32,
+    ) -> (GameRecord, GameState, Option<SearchStats>) {
+        let state = board.initial_state(seed);
+        let mut agent = self.build(params, rng::agent_seed(seed, repetition));
+        let (record, state) = GameRecord::play(agent.as_mut(), &state);
+
+        // Without the `stats` feature every counter would read zero
+        let stats = agent.stats().filter(|_| stats::ENABLED).cloned();
+
+        (record, state, stats)
+    }
+}
+
+pub fn run(args: &MazeArgs) -> io::Result<()> {
+    args.board.check().map_err(crate::invalid_input)?;
+    args.agent
+        .check(&args.params)
+        .map_err(crate::invalid_input)?;
+    let mut harness = args.run.harness();
+    let mut records = Records::default();
+    let mut last_game = None;
+    let name = args.agent.name();
+
+    records.play(
+        &mut harness,
+        "maze",
+        &name,
+        &name,
+        &args.board,
+        &args.params,
+        |seed, repetition| {
+            let (record, state, stats) =
+                args.agent.play(&args.board, &args.params, seed, repetition);
+            let result = (state.score, state.turn(), stats.clone());
+            last_game = Some((record, state, stats));
+
+            result
+        },
+    );
+
+    if let (Some(path), Some((record, _, _))) = (&args.record, &last_game) {
+        fs::write(path, record.to_string())?;
+    }
+    if let (true, Some((record, _, _))) = (args.render.is_requested(), &last_game) {
+        let frames = record
+            .replay()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        args.render
+            .write(&name, &frames.iter().map(Frame::from).collect::<Vec<_>>())?;
+    }
+
+    let preamble = match (harness.seeds(), last_game) {
+        ([seed], Some((_, state, stats))) => {
+            let stats = stats.map(|stats| stats.to_string()).unwrap_or_default();
+            format!("seed:\t{}\n{:?}\n{}", seed, state, stats)
+        }
+        _ => String::new(),
+    };
+    args.output.write(&preamble, &harness, &records)
+}
+
+#[derive(Args)]
+pub struct ReplayArgs {
+    path: PathBuf,
+    #[command(flatten)]
+    render: RenderArgs,
+}
+
+// Loads a game record, plays its actions again and prints every frame,
+// drawing them too if asked to
+pub fn replay(args: &ReplayArgs) -> io::Result<()> {
+    let record = load_record(&args.path)?;
+    let frames = record
+        .replay()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    for (i, frame) in frames.iter().enumerate() {
+        if i > 0 {
+            println!("action:\t{}", record.actions[i - 1]);
+        }
+        println!("{:?}", frame);
+    }
+
+    args.render.write(
+        &args.path.display().to_string(),
+        &frames.iter().map(Frame::from).collect::<Vec<_>>(),
+    )
+}
+
+#[derive(Args)]
+pub struct TreeArgs {
+    #[arg(long, value_enum)]
+    agent: MazeAgent,
+    #[command(flatten)]
+    params: MazeParams,
+    /// Position to search from, in the format of `cli generate`; standard
+    /// input when omitted
+    input: Option<PathBuf>,
+    /// Nodes to keep before the rest of the tree is dropped
+    #[arg(long, default_value_t = 500)]
+    max_nodes: usize,
+    /// Write the graph here instead of standard output
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+// Prints the tree a search agent explores to choose its action from a
+// position, as a Graphviz graph, e.g.
+//   cli generate --seed 3 | cli tree --agent beam-search | dot -Tsvg > tree.svg
+pub fn tree(args: &TreeArgs) -> io::Result<()> {
+    let mut input = String::new();
+    match &args.input {
+        Some(path) => input = fs::read_to_string(path)?,
+        None => _ = io::stdin().read_to_string(&mut input)?,
+    }
+    let state = contest::read_input(&input)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    args.agent
+        .check(&args.params)
+        .map_err(crate::invalid_input)?;
+
+    let mut agent: Box<dyn Agent<GameState, Action>> = match args.agent {
+        MazeAgent::BeamSearch => {
+            let mut agent =
+                BeamSearchAgent::new(args.params.beam_width.get(), args.params.beam_depth.get());
+            agent.record_search_tree(args.max_nodes);
+            Box::new(agent)
+        }
+        MazeAgent::ChokudaiSearch => {
+            let mut agent = ChokudaiSearchAgent::new(
+                args.params.beam_width.get(),
+                args.params.beam_depth.get(),
+                args.params.beam_count.get(),
+            );
+            agent.record_search_tree(args.max_nodes);
+            Box::new(agent)
+        }
+        agent => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} does not record a search tree", agent.name()),
+            ))
+        }
+    };
+    agent.choose_action(&state);
+    let dot = agent
+        .search_tree()
+        .map(|tree| tree.to_dot())
+        .unwrap_or_default();
+
+    match &args.output {
+        Some(path) => fs::write(path, dot),
+        None => io::stdout().lock().write_all(dot.as_bytes()),
+    }
+}
+
+fn load_record(path: &Path) -> io::Result<GameRecord> {
+    fs::read_to_string(path)?
+        .parse()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
@@ -0,0 +1,236 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+use harness::Harness;
+use maze_lib::stats::SearchStats;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::play_games;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// Summary statistics per agent
+    Table,
+    /// One JSON object per game
+    Jsonl,
+    /// One row per game, nested fields joined with dots
+    Csv,
+}
+
+#[derive(Args)]
+pub struct OutputArgs {
+    #[arg(long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+    /// Write the results to this file instead of standard output
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+// Everything known about one played game
+#[derive(Serialize)]
+struct GameRecord<'a, B, P> {
+    game: &'a str,
+    seed: u64,
+    repetition: u32,
+    board: &'a B,
+    agent: &'a str,
+    name: &'a str,
+    params: &'a P,
+    score: i32,
+    turns: i32,
+    elapsed_ms: f64,
+    // Only for agents that report search statistics
+    nodes_generated: Option<u64>,
+    nodes_expanded: Option<u64>,
+    max_depth: Option<usize>,
+}
+
+// Collects a record per game alongside the harness summary
+#[derive(Default)]
+pub struct Records {
+    records: Vec<Value>,
+}
+
+impl Records {
+    // Plays one agent on every seed of the harness like play_games. The
+    // closure returns the final score, the number of turns played and the
+    // agent's search statistics if it keeps any.
+    #[allow(clippy::too_many_arguments)]
+    pub fn play<B, P, F>(
+        &mut self,
+        harness: &mut Harness,
+        game: &str,
+        agent: &str,
+        name: &str,
+        board: &B,
+        params: &P,
+        mut play_game: F,
+    ) where
+        B: Serialize,
+        P: Serialize,
+        F: FnMut(u64, u32) -> (i32, i32, Option<SearchStats>),
+    {
+        let mut played = Vec::new();
+
+        play_games(harness, name, |seed, repetition| {
+            let (score, turns, stats) = play_game(seed, repetition);
+            played.push((repetition, turns, stats));
+
+            score
+        });
+
+        let result = harness.results().last().unwrap();
+        for (game_result, (repetition, turns, stats)) in result.games.iter().zip(played) {
+            let record = GameRecord {
+                game,
+                seed: game_result.seed,
+                repetition,
+                board,
+                agent,
+                name,
+                params,
+                score: game_result.score,
+                turns,
+                elapsed_ms: game_result.elapsed.as_secs_f64() * 1000.0,
+                nodes_generated: stats.as_ref().map(|stats| stats.nodes_generated),
+                nodes_expanded: stats.as_ref().map(|stats| stats.nodes_expanded),
+                max_depth: stats.as_ref().map(|stats| stats.max_depth),
+            };
+
+            self.records.push(serde_json::to_value(record).unwrap());
+        }
+    }
+}
+
+impl OutputArgs {
+    // Writes the results in the chosen format. `preamble` is only shown
+    // before a table.
+    pub fn write(&self, preamble: &str, harness: &Harness, records: &Records) -> io::Result<()> {
+        let mut out: Box<dyn Write> = match &self.output {
+            Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+            None => Box::new(io::stdout().lock()),
+        };
+
+        match self.format {
+            Format::Table => write!(out, "{}{}", preamble, harness)?,
+            Format::Jsonl => {
+                for record in &records.records {
+                    writeln!(out, "{}", record)?;
+                }
+            }
+            Format::Csv => {
+                let mut writer = csv::Writer::from_writer(out);
+
+                for (i, record) in records.records.iter().enumerate() {
+                    let mut fields = Vec::new();
+                    flatten("", record, &mut fields);
+
+                    if i == 0 {
+                        writer.write_record(fields.iter().map(|(key, _)| key))?;
+                    }
+                    writer.write_record(fields.iter().map(|(_, value)| value))?;
+                }
+
+                out = writer.into_inner().map_err(|err| err.into_error())?;
+            }
+        }
+
+        out.flush()
+    }
+}
+
+// Turns nested objects into `outer.inner` columns
+fn flatten(prefix: &str, value: &Value, fields: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(object) => flatten_object(prefix, object, fields),
+        Value::Null => fields.push((prefix.to_string(), String::new())),
+        Value::String(string) => fields.push((prefix.to_string(), string.clone())),
+        _ => fields.push((prefix.to_string(), value.to_string())),
+    }
+}
+
+fn flatten_object(prefix: &str, object: &Map<String, Value>, fields: &mut Vec<(String, String)>) {
+    for (key, value) in object {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+
+        flatten(&key, value, fields);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use harness::Harness;
+    use maze_lib::stats::SearchStats;
+    use serde::Serialize;
+
+    use super::{Format, OutputArgs, Records};
+
+    #[derive(Serialize)]
+    struct Board {
+        height: usize,
+    }
+
+    #[test]
+    fn csv_and_jsonl() {
+        let mut harness = Harness::new(vec![3, 3]);
+        let mut records = Records::default();
+        records.play(
+            &mut harness,
+            "maze",
+            "greedy",
+            "greedy, tuned",
+            &Board { height: 2 },
+            &(),
+            |seed, repetition| {
+                let stats = SearchStats {
+                    nodes_expanded: seed + 1,
+                    ..Default::default()
+                };
+                (
+                    seed as i32 + repetition as i32,
+                    4,
+                    Some(stats).filter(|_| repetition == 1),
+                )
+            },
+        );
+
+        let path = env::temp_dir().join(format!("cli-output-{}.csv", process::id()));
+        let args = OutputArgs {
+            format: Format::Csv,
+            output: Some(path.clone()),
+        };
+        args.write("", &harness, &records).unwrap();
+        let csv = fs::read_to_string(&path).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("game,seed,repetition,board.height,agent,name,params,score"));
+        assert!(lines[2].starts_with("maze,3,1,2,greedy,\"greedy, tuned\",,4,4,"));
+        assert!(lines[2].ends_with(",0,4,0"));
+
+        let args = OutputArgs {
+            format: Format::Jsonl,
+            output: Some(path.clone()),
+        };
+        args.write("", &harness, &records).unwrap();
+        let jsonl = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let first: serde_json::Value = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+        assert_eq!(first["board"]["height"], 2);
+        assert_eq!(first["score"], 3);
+        assert!(first["nodes_expanded"].is_null());
+        let second: serde_json::Value =
+            serde_json::from_str(jsonl.lines().nth(1).unwrap()).unwrap();
+        assert_eq!(second["nodes_expanded"], 4);
+    }
+}
@@ -0,0 +1,310 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::{fs, io};
+
+use automovemaze_lib::automovemaze::{Placement, State};
+use clap::Args;
+use maze_lib::maze::GameState;
+
+const CELL: usize = 40;
+const HEADER: usize = 24;
+// One colour per piece, reused when there are more pieces than colours
+const COLORS: [&str; 6] = [
+    "#1f77b4", "#d62728", "#2ca02c", "#9467bd", "#8c564b", "#e377c2",
+];
+
+#[derive(Args)]
+pub struct RenderArgs {
+    /// Write the last game as a self-contained HTML page with a turn slider
+    #[arg(long)]
+    html: Option<PathBuf>,
+    /// Write one SVG per turn of the last game into this directory
+    #[arg(long)]
+    svg_dir: Option<PathBuf>,
+}
+
+// One turn of either game, as much as the renderer needs of it
+pub struct Frame {
+    turn: i32,
+    score: i32,
+    // Indexed [y][x]
+    points: Vec<Vec<i32>>,
+    // (y, x) of the player, or of each character
+    pieces: Vec<(usize, usize)>,
+}
+
+impl From<&GameState> for Frame {
+    fn from(state: &GameState) -> Self {
+        Frame {
+            turn: state.turn(),
+            score: state.score,
+            points: state.rows(),
+            pieces: vec![state.player()],
+        }
+    }
+}
+
+impl From<&State> for Frame {
+    fn from(state: &State) -> Self {
+        Frame {
+            turn: state.turn(),
+            score: state.score(),
+            points: (0..state.h())
+                .map(|y| (0..state.w()).map(|x| state.point(y, x)).collect())
+                .collect(),
+            pieces: state.placement().coords().to_vec(),
+        }
+    }
+}
+
+impl RenderArgs {
+    pub fn is_requested(&self) -> bool {
+        self.html.is_some() || self.svg_dir.is_some()
+    }
+
+    pub fn write(&self, title: &str, frames: &[Frame]) -> io::Result<()> {
+        if let Some(path) = &self.html {
+            fs::write(path, html(title, frames))?;
+        }
+        if let Some(dir) = &self.svg_dir {
+            fs::create_dir_all(dir)?;
+            for i in 0..frames.len() {
+                fs::write(dir.join(format!("turn-{:03}.svg", i)), svg(frames, i))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Every turn of an automovemaze game, from the characters being placed on
+// the initial state to the end
+pub fn automovemaze_frames(initial_state: &State, placement: &Placement) -> Vec<Frame> {
+    let mut state = initial_state.clone();
+    for (i, &(y, x)) in placement.coords().iter().enumerate() {
+        state.set_character_coord(i, x, y);
+    }
+
+    let mut frames = vec![Frame::from(&state)];
+    while !state.is_game_over() {
+        state.advance();
+        frames.push(Frame::from(&state));
+    }
+
+    frames
+}
+
+// Draws frames[index]: cells shaded by their points, the path each piece
+// took so far and the pieces themselves
+pub fn svg(frames: &[Frame], index: usize) -> String {
+    let frame = &frames[index];
+    let (h, w) = (frame.points.len(), frame.points[0].len());
+    // Shades are relative to the richest cell of the first frame, so a cell
+    // keeps its colour until it is emptied
+    let max_point = frames[0]
+        .points
+        .iter()
+        .flatten()
+        .max()
+        .copied()
+        .unwrap_or(0)
+        .max(1);
+    let center = |(y, x): (usize, usize)| (x * CELL + CELL / 2, y * CELL + CELL / 2);
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+         viewBox=\"0 0 {0} {1}\" font-family=\"sans-serif\">",
+        w * CELL,
+        h * CELL + HEADER
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        "<text x=\"4\" y=\"17\" font-size=\"14\">turn {}, score {}</text>",
+        frame.turn, frame.score
+    )
+    .unwrap();
+    writeln!(svg, "<g transform=\"translate(0 {})\">", HEADER).unwrap();
+
+    for (y, row) in frame.points.iter().enumerate() {
+        for (x, &point) in row.iter().enumerate() {
+            let shade = 1.0 - point.max(0) as f64 / max_point as f64;
+            writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{size}\" height=\"{size}\" \
+                 fill=\"rgb(255,{},{})\" stroke=\"#999\"/>",
+                x * CELL,
+                y * CELL,
+                (165.0 + 90.0 * shade) as u8,
+                (255.0 * shade) as u8,
+                size = CELL
+            )
+            .unwrap();
+            if point > 0 {
+                let (cx, cy) = center((y, x));
+                writeln!(
+                    svg,
+                    "<text x=\"{}\" y=\"{}\" font-size=\"14\" text-anchor=\"middle\">{}</text>",
+                    cx,
+                    cy + 5,
+                    point
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    for (i, &piece) in frame.pieces.iter().enumerate() {
+        let color = COLORS[i % COLORS.len()];
+
+        // Steps wrapping around the board are left out rather than drawn
+        // across it
+        for step in frames[..=index].windows(2) {
+            let ((y0, x0), (y1, x1)) = (step[0].pieces[i], step[1].pieces[i]);
+            if y0.abs_diff(y1) > 1 || x0.abs_diff(x1) > 1 {
+                continue;
+            }
+
+            let ((x0, y0), (x1, y1)) = (center((y0, x0)), center((y1, x1)));
+            writeln!(
+                svg,
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" \
+                 stroke-width=\"4\" stroke-opacity=\"0.5\" stroke-linecap=\"round\"/>",
+                x0, y0, x1, y1, color
+            )
+            .unwrap();
+        }
+
+        let (cx, cy) = center(piece);
+        writeln!(
+            svg,
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke=\"black\"/>",
+            cx,
+            cy,
+            CELL / 4,
+            color
+        )
+        .unwrap();
+    }
+
+    svg.push_str("</g>\n</svg>\n");
+
+    svg
+}
+
+// Text as it has to appear inside an element or a quoted attribute
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+// A page showing one frame at a time, chosen with a slider. Everything is
+// inline so the file can be opened straight from disk. The title may be a
+// file name, so it is escaped.
+pub fn html(title: &str, frames: &[Frame]) -> String {
+    let mut html = String::new();
+    writeln!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>",
+        escape(title)
+    )
+    .unwrap();
+    writeln!(
+        html,
+        "<p><input type=\"range\" id=\"turn\" min=\"0\" max=\"{}\" value=\"0\"></p>",
+        frames.len() - 1
+    )
+    .unwrap();
+
+    for i in 0..frames.len() {
+        let hidden = if i == 0 { "" } else { " hidden" };
+        writeln!(html, "<div class=\"frame\"{}>", hidden).unwrap();
+        html.push_str(&svg(frames, i));
+        html.push_str("</div>\n");
+    }
+
+    html.push_str(
+        "<script>\n\
+         const slider = document.getElementById(\"turn\");\n\
+         const frames = document.querySelectorAll(\".frame\");\n\
+         slider.addEventListener(\"input\", () => {\n\
+         \x20   frames.forEach((frame, i) => frame.hidden = i != slider.value);\n\
+         });\n\
+         </script>\n\
+         </body>\n\
+         </html>\n",
+    );
+
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use automovemaze_lib::automovemaze::{MovementRule, Placement, State};
+    use maze_lib::maze::{Action, GameRecord, GameState};
+
+    use super::Frame;
+
+    #[test]
+    fn renders_maze_record() {
+        let state = GameState::from_board(&[vec![0, 2], vec![3, 4]], (0, 0), 2);
+        let record = GameRecord {
+            actions: vec![Action::Right, Action::Down],
+            scores: vec![2, 6],
+            ..GameRecord::new(&state)
+        };
+        let frames = record
+            .replay()
+            .unwrap()
+            .iter()
+            .map(Frame::from)
+            .collect::<Vec<_>>();
+
+        let last = super::svg(&frames, 2);
+        assert!(last.contains(">turn 2, score 6</text>"));
+        assert_eq!(last.matches("<rect").count(), 4);
+        assert_eq!(last.matches("<line").count(), 2);
+        assert_eq!(last.matches("<circle").count(), 1);
+        // Only the cell that was never visited still shows its points
+        assert_eq!(last.matches("text-anchor").count(), 1);
+
+        let html = super::html("maze", &frames);
+        assert!(html.contains("max=\"2\""));
+        assert_eq!(html.matches("<svg").count(), 3);
+        assert_eq!(html.matches(" hidden>").count(), 2);
+    }
+
+    #[test]
+    fn renders_automovemaze_game() {
+        let state = State::new(0, 3, 3, 2, 4, MovementRule::default());
+        let placement = Placement::new(vec![(0, 0), (2, 2)]);
+        let frames = super::automovemaze_frames(&state, &placement);
+
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[4].score, state.simulate(&placement).score());
+        assert_eq!(super::svg(&frames, 4).matches("<circle").count(), 2);
+    }
+
+    #[test]
+    fn escapes_title() {
+        let state = GameState::from_board(&[vec![0, 2]], (0, 0), 1);
+        let frames = vec![Frame::from(&state)];
+        let html = super::html("<b>\"Tom & Jerry's\".txt", &frames);
+
+        assert!(html.contains("<title>&lt;b&gt;&quot;Tom &amp; Jerry&#39;s&quot;.txt</title>"));
+        assert!(!html.contains("<b>"));
+    }
+}
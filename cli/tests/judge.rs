@@ -0,0 +1,80 @@
+use std::process::{Command, Output};
+
+const CLI: &str = env!("CARGO_BIN_EXE_cli");
+
+fn judge(time_limit: u64, agent: &[&str]) -> Output {
+    Command::new(CLI)
+        .args(["judge", "--seed", "3", "--time-limit"])
+        .arg(time_limit.to_string())
+        .arg("--")
+        .args(agent)
+        .output()
+        .unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn plays_interactive_agent() {
+    let output = judge(5000, &[CLI, "interact", "--agent", "greedy"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    // Same board and agent as `cli maze`, so the same score
+    let played = Command::new(CLI)
+        .args(["maze", "--agent", "greedy", "--seed", "3"])
+        .output()
+        .unwrap();
+    let score = |output: &Output| {
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find(|line| line.starts_with("score:"))
+            .map(str::to_string)
+    };
+    assert!(score(&output).is_some());
+    assert_eq!(score(&output), score(&played));
+}
+
+#[test]
+fn agent_keeps_running() {
+    // Plays the game, then never exits
+    let agent = format!("{} interact --agent greedy; while :; do :; done", CLI);
+    let output = judge(1000, &["sh", "-c", &agent]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("score:"));
+}
+
+#[test]
+fn time_limit() {
+    let output = judge(50, &["sleep", "5"]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        stderr(&output),
+        "error: turn 1: no answer within the time limit\n"
+    );
+}
+
+#[test]
+fn illegal_action() {
+    // Always moves up, so it leaves the 3x3 board within three turns
+    let agent = "while read word rest; do [ \"$word\" = turn ] && echo U; done";
+    let output = judge(5000, &["sh", "-c", agent]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).ends_with(": U moves off the board\n"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn agent_exits() {
+    let output = judge(5000, &["true"]);
+
+    assert!(!output.status.success());
+    assert_eq!(stderr(&output), "error: turn 1: the agent exited\n");
+}
@@ -0,0 +1,222 @@
+use std::fmt::{Display, Formatter, Result};
+use std::time::{Duration, Instant};
+
+// Plays every agent on the same list of seeds and summarises their scores
+pub struct Harness {
+    seeds: Vec<u64>,
+    results: Vec<AgentResult>,
+    // Agent the others are compared to seed by seed, e.g. an exact solver
+    reference: Option<String>,
+}
+
+pub struct AgentResult {
+    pub name: String,
+    pub games: Vec<GameResult>,
+}
+
+pub struct GameResult {
+    pub seed: u64,
+    pub score: i32,
+    pub elapsed: Duration,
+}
+
+pub struct Summary {
+    pub games: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: i32,
+    pub max: i32,
+    // 95% confidence interval of the mean, using the normal approximation
+    pub ci95: (f64, f64),
+    pub mean_elapsed: Duration,
+}
+
+impl Harness {
+    pub fn new(seeds: Vec<u64>) -> Self {
+        Harness {
+            seeds,
+            results: Vec::new(),
+            reference: None,
+        }
+    }
+
+    pub fn seeds(&self) -> &[u64] {
+        self.seeds.as_ref()
+    }
+
+    pub fn results(&self) -> &[AgentResult] {
+        self.results.as_ref()
+    }
+
+    // Adds each agent's gap to the named agent to the table. Fails when no
+    // agent of that name has been run.
+    pub fn set_reference(&mut self, name: &str) -> std::result::Result<(), String> {
+        if !self.results.iter().any(|result| result.name == name) {
+            return Err(format!("no agent named {} to compare to", name));
+        }
+
+        self.reference = Some(name.to_string());
+        Ok(())
+    }
+
+    fn reference(&self) -> Option<&AgentResult> {
+        let name = self.reference.as_ref()?;
+
+        self.results.iter().find(|result| &result.name == name)
+    }
+
+    // Play one game per seed with play_game, which builds the initial state
+    // from the seed, plays it and returns the final score
+    pub fn run<F>(&mut self, name: &str, mut play_game: F) -> &AgentResult
+    where
+        F: FnMut(u64) -> i32,
+    {
+        let games = self
+            .seeds
+            .iter()
+            .map(|&seed| {
+                let started_at = Instant::now();
+                let score = play_game(seed);
+
+                GameResult {
+                    seed,
+                    score,
+                    elapsed: started_at.elapsed(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.results.push(AgentResult {
+            name: name.to_string(),
+            games,
+        });
+
+        self.results.last().unwrap()
+    }
+}
+
+impl AgentResult {
+    pub fn total_score(&self) -> i32 {
+        self.games.iter().map(|game| game.score).sum()
+    }
+
+    pub fn summary(&self) -> Summary {
+        let n = self.games.len();
+        let scores = self.games.iter().map(|game| game.score).collect::<Vec<_>>();
+        let mean = scores.iter().sum::<i32>() as f64 / n.max(1) as f64;
+        let variance = scores
+            .iter()
+            .map(|&score| (score as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n.saturating_sub(1).max(1) as f64;
+        let std_dev = variance.sqrt();
+        let margin = 1.96 * std_dev / (n.max(1) as f64).sqrt();
+        let elapsed = self.games.iter().map(|game| game.elapsed).sum::<Duration>();
+
+        Summary {
+            games: n,
+            mean,
+            std_dev,
+            min: scores.iter().copied().min().unwrap_or(0),
+            max: scores.iter().copied().max().unwrap_or(0),
+            ci95: (mean - margin, mean + margin),
+            mean_elapsed: elapsed / n.max(1) as u32,
+        }
+    }
+
+    // Mean amount the reference scored above this agent over the same
+    // games, and the number of games where it did not score below it
+    pub fn gap(&self, reference: &AgentResult) -> (f64, usize) {
+        let gaps = self
+            .games
+            .iter()
+            .zip(&reference.games)
+            .map(|(game, reference)| reference.score - game.score)
+            .collect::<Vec<_>>();
+
+        (
+            gaps.iter().sum::<i32>() as f64 / gaps.len().max(1) as f64,
+            gaps.iter().filter(|&&gap| gap <= 0).count(),
+        )
+    }
+}
+
+impl Display for Harness {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let width = self
+            .results
+            .iter()
+            .map(|result| result.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("agent".len());
+
+        let reference = self.reference();
+
+        write!(
+            f,
+            "{:<width$}  {:>5}  {:>8}  {:>8}  {:>6}  {:>6}  {:>19}  {:>12}",
+            "agent", "games", "mean", "std dev", "min", "max", "95% CI", "time/game"
+        )?;
+        if reference.is_some() {
+            write!(f, "  {:>8}  {:>7}", "gap", "matched")?;
+        }
+        writeln!(f)?;
+
+        for result in &self.results {
+            let summary = result.summary();
+
+            write!(
+                f,
+                "{:<width$}  {:>5}  {:>8.2}  {:>8.2}  {:>6}  {:>6}  {:>19}  {:>9.3} ms",
+                result.name,
+                summary.games,
+                summary.mean,
+                summary.std_dev,
+                summary.min,
+                summary.max,
+                format!("[{:.2}, {:.2}]", summary.ci95.0, summary.ci95.1),
+                summary.mean_elapsed.as_secs_f64() * 1000.0
+            )?;
+            if let Some(reference) = reference {
+                let (gap, matched) = result.gap(reference);
+                write!(f, "  {:>8.2}  {:>7}", gap, matched)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Harness;
+
+    #[test]
+    fn summary() {
+        let mut harness = Harness::new(vec![2, 4, 4, 4, 5, 5, 7, 9]);
+        let summary = harness.run("identity", |seed| seed as i32).summary();
+
+        assert_eq!(summary.games, 8);
+        assert_eq!(summary.mean, 5.0);
+        assert!((summary.std_dev - (32.0f64 / 7.0).sqrt()).abs() < 1e-9);
+        assert_eq!((summary.min, summary.max), (2, 9));
+        assert!((summary.ci95.0 + summary.ci95.1 - 10.0).abs() < 1e-9);
+        assert_eq!(harness.to_string().lines().count(), 2);
+    }
+
+    #[test]
+    fn gap() {
+        let mut harness = Harness::new(vec![1, 2, 3, 4]);
+        harness.run("exact", |seed| 10 * seed as i32);
+        harness.run("close", |seed| 10 * seed as i32 - (seed % 2) as i32);
+
+        assert_eq!(harness.results()[1].gap(&harness.results()[0]), (0.5, 2));
+        assert!(harness.set_reference("missing").is_err());
+        harness.set_reference("exact").unwrap();
+        let table = harness.to_string();
+        assert!(table.lines().next().unwrap().ends_with("gap  matched"));
+        assert!(table.lines().nth(2).unwrap().ends_with("0.50        2"));
+    }
+}
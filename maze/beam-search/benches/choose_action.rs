@@ -0,0 +1,25 @@
+use beam_search::BeamSearchAgent;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use lib::{maze::GameState, Agent};
+
+// One decision on a fresh board of each size, as the agent binary sets it up
+fn choose_action(c: &mut Criterion) {
+    let mut group = c.benchmark_group("maze/beam-search");
+
+    for (size, turns) in [(3, 4), (10, 20), (30, 60)] {
+        let state = GameState::new(size, size, turns, 0);
+        let mut agent = BeamSearchAgent::new(2, 4);
+        let label = format!("{}x{}", size, size);
+
+        group.bench_with_input(
+            BenchmarkId::new("choose_action", &label),
+            &state,
+            |b, state| b.iter(|| agent.choose_action(state)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, choose_action);
+criterion_main!(benches);
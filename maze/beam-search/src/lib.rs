@@ -0,0 +1,181 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use lib::{
+    maze::{Action, GameState},
+    search_tree::SearchTree,
+    stats::SearchStats,
+    Agent, GameStateBase,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BeamSearchParams {
+    pub beam_width: usize,
+    pub beam_depth: usize,
+}
+
+pub struct BeamSearchAgent {
+    params: BeamSearchParams,
+    stats: SearchStats,
+    tree: SearchTree<Action>,
+}
+
+struct BeamSearchState {
+    state: GameState,
+    first_action: Option<Action>,
+    node: Option<usize>,
+}
+
+impl PartialEq for BeamSearchState {
+    fn eq(&self, other: &Self) -> bool {
+        self.state.score == other.state.score
+    }
+}
+
+impl Eq for BeamSearchState {}
+
+impl PartialOrd for BeamSearchState {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BeamSearchState {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.state.score.cmp(&other.state.score)
+    }
+}
+
+impl BeamSearchAgent {
+    pub fn new(beam_width: usize, beam_depth: usize) -> Self {
+        Self::with_params(BeamSearchParams {
+            beam_width,
+            beam_depth,
+        })
+    }
+
+    pub fn with_params(params: BeamSearchParams) -> Self {
+        assert!(params.beam_width > 0, "the beam needs to keep a state");
+        assert!(params.beam_depth > 0, "the search needs to look a turn ahead");
+
+        BeamSearchAgent {
+            params,
+            stats: SearchStats::default(),
+            tree: SearchTree::default(),
+        }
+    }
+
+    pub fn params(&self) -> &BeamSearchParams {
+        &self.params
+    }
+
+    // Keeps up to `max_nodes` nodes of each turn's search, see search_tree()
+    pub fn record_search_tree(&mut self, max_nodes: usize) {
+        self.tree = SearchTree::new(max_nodes);
+    }
+}
+
+impl Agent<GameState, Action> for BeamSearchAgent {
+    fn choose_action(&mut self, state: &GameState) -> Option<Action> {
+        let timer = self.stats.start_turn();
+        let mut beam = BinaryHeap::new();
+        beam.push(BeamSearchState {
+            state: state.clone(),
+            first_action: None,
+            node: self.tree.start(state.score),
+        });
+
+        for depth in 0..self.params.beam_depth {
+            let mut next_beam = BinaryHeap::new();
+
+            while let Some(BeamSearchState {
+                state,
+                first_action,
+                node,
+            }) = beam.pop()
+            {
+                self.stats.expand(depth);
+                for action in state.legal_actions() {
+                    let mut new_state = state.clone();
+                    new_state.advance(action);
+                    self.stats.generate(depth + 1);
+                    let node = self.tree.add(node, action, new_state.score);
+                    next_beam.push(BeamSearchState {
+                        state: new_state,
+                        first_action: first_action.or(Some(action)),
+                        node,
+                    });
+                }
+            }
+
+            if next_beam.is_empty() {
+                break;
+            }
+
+            beam = next_beam
+                .into_sorted_vec()
+                .into_iter()
+                .rev()
+                .take(self.params.beam_width)
+                .collect::<BinaryHeap<_>>();
+            for s in beam.iter() {
+                self.tree.survive(s.node);
+            }
+        }
+
+        let action = beam.pop().and_then(|s| s.first_action);
+        self.stats.end_turn(timer);
+
+        action
+    }
+
+    fn stats(&self) -> Option<&SearchStats> {
+        Some(&self.stats)
+    }
+
+    fn search_tree(&self) -> Option<&SearchTree<Action>> {
+        Some(&self.tree).filter(|tree| tree.is_recording())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use harness::Harness;
+    use lib::Agent;
+
+    #[test]
+    fn score() {
+        let mut harness = Harness::new((0..100).collect());
+        harness.run("beam-search", |seed| {
+            let initial_state = super::GameState::new(3, 3, 4, rng::board_seed(seed));
+            let last_state = super::BeamSearchAgent::new(2, 4).play_game(&initial_state);
+            last_state.score
+        });
+
+        println!("{}", harness);
+        assert_eq!(harness.results()[0].total_score(), 2545);
+    }
+
+    #[test]
+    fn search_tree() {
+        let state = super::GameState::new(3, 3, 4, 0);
+        let mut agent = super::BeamSearchAgent::new(2, 4);
+        agent.record_search_tree(1000);
+        let action = agent.choose_action(&state);
+
+        assert_eq!(
+            action,
+            super::BeamSearchAgent::new(2, 4).choose_action(&state)
+        );
+        let tree = agent.search_tree().unwrap();
+        assert_eq!(tree.dropped(), 0);
+        for depth in 1..=4 {
+            let survivors = tree
+                .nodes()
+                .iter()
+                .filter(|node| node.depth == depth && node.survived)
+                .count();
+            assert_eq!(survivors, 2);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::{cell::RefCell, cmp::Ordering, collections::BinaryHeap};
+
+use lib::{
+    maze::{Action, GameState},
+    search_tree::SearchTree,
+    stats::SearchStats,
+    Agent, GameStateBase,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChokudaiSearchParams {
+    pub beam_width: usize,
+    pub beam_depth: usize,
+    pub beam_count: usize,
+}
+
+pub struct ChokudaiSearchAgent {
+    params: ChokudaiSearchParams,
+    stats: SearchStats,
+    tree: SearchTree<Action>,
+}
+
+struct ChokudaiSearchState {
+    game_state: GameState,
+    first_action: Option<Action>,
+    node: Option<usize>,
+}
+
+impl PartialEq for ChokudaiSearchState {
+    fn eq(&self, other: &Self) -> bool {
+        self.game_state.score == other.game_state.score
+    }
+}
+
+impl Eq for ChokudaiSearchState {}
+
+impl PartialOrd for ChokudaiSearchState {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ChokudaiSearchState {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.game_state.score.cmp(&other.game_state.score)
+    }
+}
+
+impl ChokudaiSearchAgent {
+    pub fn new(beam_width: usize, beam_depth: usize, beam_count: usize) -> Self {
+        Self::with_params(ChokudaiSearchParams {
+            beam_width,
+            beam_depth,
+            beam_count,
+        })
+    }
+
+    // Beams are filled up to depth - 1, so a depth of 1 never leaves the
+    // current state
+    pub fn with_params(params: ChokudaiSearchParams) -> Self {
+        assert!(params.beam_width > 0, "the beams need to expand a state");
+        assert!(params.beam_depth > 1, "the search needs a beam depth of at least 2");
+        assert!(params.beam_count > 0, "at least one beam is needed");
+
+        ChokudaiSearchAgent {
+            params,
+            stats: SearchStats::default(),
+            tree: SearchTree::default(),
+        }
+    }
+
+    pub fn params(&self) -> &ChokudaiSearchParams {
+        &self.params
+    }
+
+    // Keeps up to `max_nodes` nodes of each turn's search, see search_tree().
+    // A node survives when a beam picks it for expansion.
+    pub fn record_search_tree(&mut self, max_nodes: usize) {
+        self.tree = SearchTree::new(max_nodes);
+    }
+}
+
+impl Agent<GameState, Action> for ChokudaiSearchAgent {
+    fn choose_action(&mut self, state: &GameState) -> Option<Action> {
+        let timer = self.stats.start_turn();
+        let beams = (0..(self.params.beam_depth + 1))
+            .map(|_| RefCell::new(BinaryHeap::new()))
+            .collect::<Vec<_>>();
+        beams[0].borrow_mut().push(ChokudaiSearchState {
+            game_state: state.clone(),
+            first_action: None,
+            node: self.tree.start(state.score),
+        });
+
+        for _ in 0..self.params.beam_count {
+            for t in 0..self.params.beam_depth - 1 {
+                let mut current_beam = beams[t].borrow_mut();
+                let mut next_beam = beams[t + 1].borrow_mut();
+
+                for _ in 0..self.params.beam_width {
+                    if current_beam.is_empty() {
+                        break;
+                    }
+                    if current_beam.peek().unwrap().game_state.is_game_over() {
+                        break;
+                    }
+
+                    let ChokudaiSearchState {
+                        game_state: current_game_state,
+                        first_action,
+                        node,
+                    } = current_beam.pop().unwrap();
+
+                    self.stats.expand(t);
+                    self.tree.survive(node);
+                    for action in current_game_state.legal_actions() {
+                        let mut next_game_state = current_game_state.clone();
+                        next_game_state.advance(action);
+                        self.stats.generate(t + 1);
+
+                        let node = self.tree.add(node, action, next_game_state.score);
+                        next_beam.push(ChokudaiSearchState {
+                            game_state: next_game_state,
+                            first_action: first_action.or(Some(action)),
+                            node,
+                        });
+                    }
+                }
+            }
+        }
+
+        let action = beams
+            .into_iter()
+            .rev()
+            .find_map(|beam| beam.borrow_mut().pop())
+            .and_then(|state| {
+                self.tree.survive(state.node);
+                state.first_action
+            });
+        self.stats.end_turn(timer);
+
+        action
+    }
+
+    fn stats(&self) -> Option<&SearchStats> {
+        Some(&self.stats)
+    }
+
+    fn search_tree(&self) -> Option<&SearchTree<Action>> {
+        Some(&self.tree).filter(|tree| tree.is_recording())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use harness::Harness;
+    use lib::Agent;
+
+    #[test]
+    fn score() {
+        let mut harness = Harness::new((0..100).collect());
+        harness.run("chokudai-search", |seed| {
+            let initial_state = super::GameState::new(3, 3, 4, rng::board_seed(seed));
+            let last_state = super::ChokudaiSearchAgent::new(2, 4, 2).play_game(&initial_state);
+            last_state.score
+        });
+
+        println!("{}", harness);
+        assert_eq!(harness.results()[0].total_score(), 2578);
+    }
+
+    #[test]
+    fn search_tree() {
+        let state = super::GameState::new(3, 3, 4, 0);
+        let mut agent = super::ChokudaiSearchAgent::new(2, 4, 2);
+        agent.record_search_tree(10);
+        let action = agent.choose_action(&state);
+
+        assert_eq!(
+            action,
+            super::ChokudaiSearchAgent::new(2, 4, 2).choose_action(&state)
+        );
+        let tree = agent.search_tree().unwrap();
+        assert_eq!(tree.nodes().len(), 10);
+        assert!(tree.dropped() > 0);
+        assert!(tree.nodes()[0].survived);
+        assert!(tree.to_dot().contains("more nodes"));
+    }
+}
@@ -0,0 +1,37 @@
+use lib::{
+    maze::{Action, GameState},
+    stats::SearchStats,
+    Agent, GameStateBase,
+};
+
+#[derive(Default)]
+pub struct GreedyAgent {
+    stats: SearchStats,
+}
+
+impl GreedyAgent {
+    pub fn new() -> Self {
+        GreedyAgent::default()
+    }
+}
+
+impl Agent<GameState, Action> for GreedyAgent {
+    fn choose_action(&mut self, state: &GameState) -> Option<Action> {
+        let timer = self.stats.start_turn();
+        self.stats.expand(0);
+
+        let action = state.legal_actions().max_by_key(|&action| {
+            let mut next_state = state.clone();
+            next_state.advance(action);
+            self.stats.generate(1);
+            next_state.score
+        });
+        self.stats.end_turn(timer);
+
+        action
+    }
+
+    fn stats(&self) -> Option<&SearchStats> {
+        Some(&self.stats)
+    }
+}
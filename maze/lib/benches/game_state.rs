@@ -0,0 +1,68 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use maze_lib::maze::GameState;
+use maze_lib::GameStateBase;
+
+const SIZES: [usize; 3] = [3, 10, 50];
+
+fn board(size: usize) -> (String, GameState) {
+    (
+        format!("{}x{}", size, size),
+        GameState::new(size, size, size as i32 * 2, 0),
+    )
+}
+
+// The primitives every search agent calls once per node
+fn primitives(c: &mut Criterion) {
+    let mut group = c.benchmark_group("maze/GameState");
+
+    for size in SIZES {
+        let (label, state) = board(size);
+        let action = state.legal_actions().next().unwrap();
+
+        group.bench_with_input(BenchmarkId::new("advance", &label), &state, |b, state| {
+            b.iter_batched(
+                || state.clone(),
+                |mut state| {
+                    state.advance(action);
+                    state
+                },
+                BatchSize::SmallInput,
+            );
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("valid_actions", &label),
+            &state,
+            |b, state| b.iter(|| black_box(state).valid_actions()),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("legal_actions", &label),
+            &state,
+            |b, state| b.iter(|| black_box(state).legal_actions().count()),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("legal_actions_into", &label),
+            &state,
+            |b, state| {
+                let mut actions = Vec::with_capacity(4);
+                b.iter(|| {
+                    black_box(state).legal_actions_into(&mut actions);
+                    actions.len()
+                })
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("clone", &label), &state, |b, state| {
+            b.iter(|| black_box(state).clone())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, primitives);
+criterion_main!(benches);
@@ -1,5 +1,29 @@
 pub trait Agent<T: GameStateBase<S>, S> {
-    fn play_game(&mut self, state: &T) -> T;
+    fn choose_action(&mut self, state: &T) -> Option<S>;
+
+    // What the agent's searches have done so far, for agents that search
+    fn stats(&self) -> Option<&stats::SearchStats> {
+        None
+    }
+
+    // The tree explored for the last action, for agents recording one
+    fn search_tree(&self) -> Option<&search_tree::SearchTree<S>> {
+        None
+    }
+
+    fn play_game(&mut self, state: &T) -> T
+    where
+        T: Clone,
+    {
+        let mut next_state = state.clone();
+
+        while !next_state.is_game_over() {
+            let action = self.choose_action(&next_state).unwrap();
+            next_state.advance(action);
+        }
+
+        next_state
+    }
 }
 
 pub trait GameStateBase<T> {
@@ -7,34 +31,56 @@ pub trait GameStateBase<T> {
 
     fn valid_actions(&self) -> Vec<T>;
 
+    // Replaces the contents of `actions` with the valid actions, reusing its
+    // allocation
+    fn legal_actions_into(&self, actions: &mut Vec<T>) {
+        actions.clear();
+        actions.extend(self.valid_actions());
+    }
+
     fn advance(&mut self, action: T);
 }
 
 pub mod maze {
-    use rand::Rng;
-    use rand::{rngs::StdRng, SeedableRng};
-    use std::fmt::{Debug, Formatter, Result};
+    use rng::Pcg32;
+    use std::fmt::{Debug, Display, Formatter, Result};
+    use std::str::FromStr;
 
-    use super::GameStateBase;
+    use super::{Agent, GameStateBase};
 
     #[derive(Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     struct Player {
         y: usize,
         x: usize,
     }
 
+    // With the serde feature a state is stored as
+    //   {"max_turns":4,"turn":0,"score":0,"player":{"y":0,"x":2},"points":[[1,2,0],...]}
+    // with points given row by row
     #[derive(Clone)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(into = "GameStateData", try_from = "GameStateData")
+    )]
     pub struct GameState {
         h: usize,
         w: usize,
         pub score: i32,
+        // Indexed [y][x], as in automovemaze
         points: Vec<Vec<i32>>,
         max_turns: i32,
         turn: i32,
         player: Player,
     }
 
-    #[derive(Clone, Copy)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(rename_all = "snake_case")
+    )]
     pub enum Action {
         Up,
         Down,
@@ -42,15 +88,63 @@ pub mod maze {
         Right,
     }
 
+    // A set of actions stored as a bitmask, iterated in Up, Down, Left, Right
+    // order without allocating
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct ActionSet(u8);
+
+    const ACTIONS: [Action; 4] = [Action::Up, Action::Down, Action::Left, Action::Right];
+
+    impl ActionSet {
+        pub fn insert(&mut self, action: Action) {
+            self.0 |= 1 << action as u8;
+        }
+
+        pub fn contains(self, action: Action) -> bool {
+            self.0 & (1 << action as u8) != 0
+        }
+
+        pub fn len(self) -> usize {
+            self.0.count_ones() as usize
+        }
+
+        pub fn is_empty(self) -> bool {
+            self.0 == 0
+        }
+    }
+
+    impl Iterator for ActionSet {
+        type Item = Action;
+
+        fn next(&mut self) -> Option<Action> {
+            if self.0 == 0 {
+                return None;
+            }
+
+            let action = ACTIONS[self.0.trailing_zeros() as usize];
+            self.0 &= self.0 - 1;
+
+            Some(action)
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let len = self.0.count_ones() as usize;
+
+            (len, Some(len))
+        }
+    }
+
+    impl ExactSizeIterator for ActionSet {}
+
     impl GameState {
         pub fn new(h: usize, w: usize, max_turns: i32, seed: u64) -> Self {
-            let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+            let mut rng = Pcg32::new(seed);
 
             let points: Vec<Vec<i32>> = (0..h)
-                .map(|_| (0..w).map(|_| rng.gen_range(1..10)).collect())
+                .map(|_| (0..w).map(|_| rng.gen_range(1..10) as i32).collect())
                 .collect();
 
-            return GameState {
+            GameState {
                 h,
                 w,
                 points,
@@ -61,32 +155,93 @@ pub mod maze {
                 max_turns,
                 turn: 0,
                 score: 0,
-            };
+            }
         }
-    }
 
-    impl GameStateBase<Action> for GameState {
-        fn is_game_over(&self) -> bool {
-            return self.turn >= self.max_turns;
+        // A game on the given board, with rows[y][x] the points of each cell
+        pub fn from_board(rows: &[Vec<i32>], start: (usize, usize), max_turns: i32) -> Self {
+            let h = rows.len();
+            let w = rows.first().map_or(0, |row| row.len());
+
+            GameState {
+                h,
+                w,
+                points: rows.to_vec(),
+                player: Player {
+                    y: start.0,
+                    x: start.1,
+                },
+                max_turns,
+                turn: 0,
+                score: 0,
+            }
         }
 
-        fn valid_actions(&self) -> Vec<Action> {
-            let mut actions = Vec::with_capacity(4);
+        pub fn h(&self) -> usize {
+            self.h
+        }
+
+        pub fn w(&self) -> usize {
+            self.w
+        }
+
+        pub fn max_turns(&self) -> i32 {
+            self.max_turns
+        }
+
+        pub fn turn(&self) -> i32 {
+            self.turn
+        }
+
+        // Position of the player as (y, x)
+        pub fn player(&self) -> (usize, usize) {
+            (self.player.y, self.player.x)
+        }
+
+        // Points still on the cell, including the one under the player
+        pub fn point(&self, y: usize, x: usize) -> i32 {
+            self.points[y][x]
+        }
+
+        // The moves that stay on the board, the allocation-free counterpart
+        // of valid_actions
+        pub fn legal_actions(&self) -> ActionSet {
+            let mut actions = ActionSet::default();
 
             if self.player.y > 0 {
-                actions.push(Action::Up);
+                actions.insert(Action::Up);
             }
             if self.player.y < self.h - 1 {
-                actions.push(Action::Down);
+                actions.insert(Action::Down);
             }
             if self.player.x > 0 {
-                actions.push(Action::Left);
+                actions.insert(Action::Left);
             }
             if self.player.x < self.w - 1 {
-                actions.push(Action::Right);
+                actions.insert(Action::Right);
             }
 
-            return actions;
+            actions
+        }
+
+        // The board as rows[y][x]
+        pub fn rows(&self) -> Vec<Vec<i32>> {
+            self.points.clone()
+        }
+    }
+
+    impl GameStateBase<Action> for GameState {
+        fn is_game_over(&self) -> bool {
+            self.turn >= self.max_turns
+        }
+
+        fn valid_actions(&self) -> Vec<Action> {
+            self.legal_actions().collect()
+        }
+
+        fn legal_actions_into(&self, actions: &mut Vec<Action>) {
+            actions.clear();
+            actions.extend(self.legal_actions());
         }
 
         fn advance(&mut self, action: Action) {
@@ -102,7 +257,7 @@ pub mod maze {
             self.player.y = (self.player.y as i32 + dy) as usize;
             self.player.x = (self.player.x as i32 + dx) as usize;
 
-            let point = &mut self.points[self.player.x][self.player.y];
+            let point = &mut self.points[self.player.y][self.player.x];
 
             self.score += *point;
 
@@ -120,8 +275,8 @@ pub mod maze {
                     .map(|x| {
                         if x == self.player.x && y == self.player.y {
                             "@".to_string()
-                        } else if 0 < self.points[x][y] {
-                            self.points[x][y].to_string()
+                        } else if 0 < self.points[y][x] {
+                            self.points[y][x].to_string()
                         } else {
                             ".".to_string()
                         }
@@ -135,4 +290,1205 @@ pub mod maze {
             Ok(())
         }
     }
+
+    // Reads the Debug rendering back, preceded by the max_turns and the
+    // points under the player, which the @ hides:
+    //
+    //   max_turns:	4
+    //   player_point:	3
+    //   turn:	0
+    //   score:	0
+    //   12@
+    //   23.
+    //   1..
+    impl FromStr for GameState {
+        type Err = ParseError;
+
+        fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+            let mut lines = s.lines().filter(|line| !line.trim().is_empty());
+            let max_turns = parse_header(lines.next(), "max_turns")?;
+            let player_point = parse_header(lines.next(), "player_point")?;
+            let turn = parse_header(lines.next(), "turn")?;
+            let score = parse_header(lines.next(), "score")?;
+
+            let mut rows = Vec::new();
+            let mut players = Vec::new();
+            for (y, line) in lines.enumerate() {
+                let row = line
+                    .trim()
+                    .chars()
+                    .enumerate()
+                    .map(|(x, c)| match c {
+                        '1'..='9' => Ok(c as i32 - '0' as i32),
+                        '.' => Ok(0),
+                        '@' => {
+                            players.push((y, x));
+                            Ok(0)
+                        }
+                        _ => Err(ParseError(format!("unexpected {:?} on the board", c))),
+                    })
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+
+                rows.push(row);
+            }
+
+            if rows.is_empty() || rows.iter().any(|row| row.len() != rows[0].len()) {
+                return Err(ParseError(
+                    "the board must be a non-empty rectangle".to_string(),
+                ));
+            }
+            let [start] = players[..] else {
+                return Err(ParseError(format!(
+                    "expected one player on the board, found {}",
+                    players.len()
+                )));
+            };
+            rows[start.0][start.1] = player_point;
+
+            let mut state = GameState::from_board(&rows, start, max_turns);
+            state.turn = turn;
+            state.score = score;
+
+            Ok(state)
+        }
+    }
+
+    // The value of a `key:\tvalue` line
+    fn parse_header<T: FromStr>(
+        line: Option<&str>,
+        key: &str,
+    ) -> std::result::Result<T, ParseError> {
+        let value = line
+            .and_then(|line| line.split_once(':'))
+            .filter(|(k, _)| k.trim() == key)
+            .map(|(_, value)| value.trim())
+            .ok_or_else(|| ParseError(format!("expected a {} line", key)))?;
+
+        value
+            .parse()
+            .map_err(|_| ParseError(format!("cannot parse {} {:?}", key, value)))
+    }
+
+    #[cfg(feature = "serde")]
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct GameStateData {
+        max_turns: i32,
+        turn: i32,
+        score: i32,
+        player: Player,
+        points: Vec<Vec<i32>>,
+    }
+
+    #[cfg(feature = "serde")]
+    impl From<GameState> for GameStateData {
+        fn from(state: GameState) -> Self {
+            GameStateData {
+                max_turns: state.max_turns,
+                turn: state.turn,
+                score: state.score,
+                points: state.rows(),
+                player: state.player,
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    impl TryFrom<GameStateData> for GameState {
+        type Error = ParseError;
+
+        fn try_from(data: GameStateData) -> std::result::Result<Self, Self::Error> {
+            let rows = &data.points;
+            if rows.is_empty() || rows.iter().any(|row| row.len() != rows[0].len()) {
+                return Err(ParseError(
+                    "points must be a non-empty rectangle".to_string(),
+                ));
+            }
+            if data.player.y >= rows.len() || data.player.x >= rows[0].len() {
+                return Err(ParseError("the player is outside the board".to_string()));
+            }
+
+            let mut state =
+                GameState::from_board(rows, (data.player.y, data.player.x), data.max_turns);
+            state.turn = data.turn;
+            state.score = data.score;
+
+            Ok(state)
+        }
+    }
+
+    impl Display for Action {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            let c = match self {
+                Action::Up => 'U',
+                Action::Down => 'D',
+                Action::Left => 'L',
+                Action::Right => 'R',
+            };
+
+            write!(f, "{}", c)
+        }
+    }
+
+    impl FromStr for Action {
+        type Err = ParseError;
+
+        fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+            match s {
+                "U" => Ok(Action::Up),
+                "D" => Ok(Action::Down),
+                "L" => Ok(Action::Left),
+                "R" => Ok(Action::Right),
+                _ => Err(ParseError(format!("unknown action {:?}", s))),
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct ParseError(pub String);
+
+    impl Display for ParseError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl std::error::Error for ParseError {}
+
+    // Everything needed to play a game again: the initial board, the actions
+    // taken and the score after each of them. Saved as text by Display and
+    // loaded by FromStr:
+    //
+    //   max_turns 4
+    //   start 0 2
+    //   points
+    //   1 2 3
+    //   4 5 6
+    //   7 8 9
+    //   actions D L L D
+    //   scores 6 11 15 22
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct GameRecord {
+        pub rows: Vec<Vec<i32>>,
+        pub start: (usize, usize),
+        pub max_turns: i32,
+        pub actions: Vec<Action>,
+        pub scores: Vec<i32>,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum ReplayError {
+        GameOver {
+            turn: i32,
+        },
+        InvalidAction {
+            turn: i32,
+            action: Action,
+        },
+        ScoreMismatch {
+            turn: i32,
+            recorded: i32,
+            replayed: i32,
+        },
+        MissingScores {
+            recorded: usize,
+            actions: usize,
+        },
+    }
+
+    impl Display for ReplayError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            match self {
+                ReplayError::GameOver { turn } => {
+                    write!(f, "turn {}: action after the game is over", turn)
+                }
+                ReplayError::InvalidAction { turn, action } => {
+                    write!(f, "turn {}: {} moves off the board", turn, action)
+                }
+                ReplayError::ScoreMismatch {
+                    turn,
+                    recorded,
+                    replayed,
+                } => write!(
+                    f,
+                    "turn {}: recorded score {} but replay scored {}",
+                    turn, recorded, replayed
+                ),
+                ReplayError::MissingScores { recorded, actions } => {
+                    write!(f, "{} scores recorded for {} actions", recorded, actions)
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for ReplayError {}
+
+    impl GameRecord {
+        pub fn new(state: &GameState) -> Self {
+            GameRecord {
+                rows: state.rows(),
+                start: state.player(),
+                max_turns: state.max_turns,
+                actions: Vec::new(),
+                scores: Vec::new(),
+            }
+        }
+
+        // Plays a whole game with the agent, recording every action
+        pub fn play<A>(agent: &mut A, state: &GameState) -> (GameRecord, GameState)
+        where
+            A: Agent<GameState, Action> + ?Sized,
+        {
+            let mut record = GameRecord::new(state);
+            let mut next_state = state.clone();
+
+            while !next_state.is_game_over() {
+                let action = agent.choose_action(&next_state).unwrap();
+                next_state.advance(action);
+                record.actions.push(action);
+                record.scores.push(next_state.score);
+            }
+
+            (record, next_state)
+        }
+
+        pub fn initial_state(&self) -> GameState {
+            GameState::from_board(&self.rows, self.start, self.max_turns)
+        }
+
+        // Applies the recorded actions again and checks the score after
+        // each of them. Returns every frame, starting with the initial state.
+        pub fn replay(&self) -> std::result::Result<Vec<GameState>, ReplayError> {
+            if self.scores.len() != self.actions.len() {
+                return Err(ReplayError::MissingScores {
+                    recorded: self.scores.len(),
+                    actions: self.actions.len(),
+                });
+            }
+
+            let mut frames = vec![self.initial_state()];
+
+            for (&action, &recorded) in self.actions.iter().zip(&self.scores) {
+                let mut state = frames.last().unwrap().clone();
+                let turn = state.turn + 1;
+
+                if state.is_game_over() {
+                    return Err(ReplayError::GameOver { turn });
+                }
+                if !state.legal_actions().contains(action) {
+                    return Err(ReplayError::InvalidAction { turn, action });
+                }
+
+                state.advance(action);
+                if state.score != recorded {
+                    return Err(ReplayError::ScoreMismatch {
+                        turn,
+                        recorded,
+                        replayed: state.score,
+                    });
+                }
+
+                frames.push(state);
+            }
+
+            Ok(frames)
+        }
+    }
+
+    impl Display for GameRecord {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            let join = |values: Vec<String>| values.join(" ");
+
+            writeln!(f, "max_turns {}", self.max_turns)?;
+            writeln!(f, "start {} {}", self.start.0, self.start.1)?;
+            writeln!(f, "points")?;
+            for row in &self.rows {
+                writeln!(f, "{}", join(row.iter().map(i32::to_string).collect()))?;
+            }
+            writeln!(
+                f,
+                "actions {}",
+                join(self.actions.iter().map(Action::to_string).collect())
+            )?;
+            writeln!(
+                f,
+                "scores {}",
+                join(self.scores.iter().map(i32::to_string).collect())
+            )
+        }
+    }
+
+    impl FromStr for GameRecord {
+        type Err = ParseError;
+
+        fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+            let lines = s
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .collect::<Vec<_>>();
+            let field = |i: usize, key: &str| {
+                let (n, line) = lines
+                    .get(i)
+                    .ok_or_else(|| ParseError(format!("missing {}", key)))?;
+                let mut words = line.split_whitespace();
+
+                if words.next() != Some(key) {
+                    return Err(ParseError(format!("line {}: expected {}", n + 1, key)));
+                }
+
+                Ok(words.collect::<Vec<_>>())
+            };
+
+            let [max_turns] = parse_all(&field(0, "max_turns")?)?[..] else {
+                return Err(ParseError("max_turns needs one number".to_string()));
+            };
+            let [y, x] = parse_all(&field(1, "start")?)?[..] else {
+                return Err(ParseError("start needs a row and a column".to_string()));
+            };
+            field(2, "points")?;
+
+            let num_rows = lines[3..]
+                .iter()
+                .take_while(|(_, line)| !line.starts_with("actions"))
+                .count();
+            let rows = lines[3..3 + num_rows]
+                .iter()
+                .map(|(_, line)| parse_all(&line.split_whitespace().collect::<Vec<_>>()))
+                .collect::<std::result::Result<Vec<Vec<i32>>, _>>()?;
+            let actions = parse_all(&field(3 + num_rows, "actions")?)?;
+            let scores = parse_all(&field(4 + num_rows, "scores")?)?;
+
+            if let Some((n, _)) = lines.get(5 + num_rows) {
+                return Err(ParseError(format!("line {}: unexpected line", n + 1)));
+            }
+            if rows.is_empty() || rows.iter().any(|row| row.len() != rows[0].len()) {
+                return Err(ParseError(
+                    "points must be a non-empty rectangle".to_string(),
+                ));
+            }
+            if y >= rows.len() || x >= rows[0].len() {
+                return Err(ParseError("start is outside the board".to_string()));
+            }
+
+            Ok(GameRecord {
+                rows,
+                start: (y, x),
+                max_turns,
+                actions,
+                scores,
+            })
+        }
+    }
+
+    fn parse_all<T: FromStr>(words: &[&str]) -> std::result::Result<Vec<T>, ParseError> {
+        words
+            .iter()
+            .map(|word| {
+                word.parse()
+                    .map_err(|_| ParseError(format!("cannot parse {:?}", word)))
+            })
+            .collect()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{Action, GameRecord, GameState, ReplayError};
+        use crate::{Agent, GameStateBase};
+
+        struct FirstAction;
+
+        impl Agent<GameState, Action> for FirstAction {
+            fn choose_action(&mut self, state: &GameState) -> Option<Action> {
+                state.legal_actions().next()
+            }
+        }
+
+        #[test]
+        fn record_round_trip() {
+            let state = GameState::new(3, 5, 6, 0);
+            let (record, last_state) = GameRecord::play(&mut FirstAction, &state);
+            let loaded = record.to_string().parse::<GameRecord>().unwrap();
+
+            assert_eq!(loaded, record);
+            assert_eq!(loaded.actions.len(), 6);
+
+            let frames = loaded.replay().unwrap();
+            assert_eq!(frames.len(), 7);
+            assert_eq!(format!("{:?}", frames[6]), format!("{:?}", last_state));
+        }
+
+        #[test]
+        fn legal_actions() {
+            let state = GameState::from_board(&[vec![1, 2, 3], vec![4, 5, 6]], (1, 1), 3);
+            let actions = state.legal_actions();
+
+            assert_eq!(actions.len(), 3);
+            assert!(actions.contains(Action::Up) && !actions.contains(Action::Down));
+            assert_eq!(
+                actions.collect::<Vec<_>>(),
+                vec![Action::Up, Action::Left, Action::Right]
+            );
+
+            let mut buf = vec![Action::Down; 8];
+            state.legal_actions_into(&mut buf);
+            assert_eq!(buf, state.valid_actions());
+        }
+
+        #[test]
+        fn non_square_board() {
+            let mut state = GameState::from_board(&[vec![0, 5, 7]], (0, 0), 2);
+            state.advance(Action::Right);
+            state.advance(Action::Right);
+
+            assert_eq!(state.score, 12);
+            assert_eq!(format!("{:?}", state).lines().last(), Some("..@"));
+
+            let state = GameState::new(2, 5, 3, 0);
+            assert_eq!(state.rows().len(), 2);
+            assert!(state.rows().iter().all(|row| row.len() == 5));
+            assert_eq!(format!("{:?}", state).lines().last().unwrap().len(), 5);
+        }
+
+        #[test]
+        fn replay_detects_tampering() {
+            let state = GameState::new(4, 4, 3, 1);
+            let (mut record, _) = GameRecord::play(&mut FirstAction, &state);
+            record.scores[1] += 1;
+
+            assert!(matches!(
+                record.replay(),
+                Err(ReplayError::ScoreMismatch { turn: 2, .. })
+            ));
+        }
+
+        #[test]
+        fn parse_errors() {
+            let record = "max_turns 1\nstart 0 0\npoints\n1 2\n3\nactions R\nscores 2\n";
+            assert!(record.parse::<GameRecord>().is_err());
+
+            let record = "max_turns 1\nstart 0 0\npoints\n1 2\nactions X\nscores 2\n";
+            assert!(record.parse::<GameRecord>().is_err());
+
+            let record = "max_turns 1\nstart 0 0\npoints\n1 2\nactions R\nscores 2\n";
+            assert_eq!(
+                record
+                    .parse::<GameRecord>()
+                    .unwrap()
+                    .replay()
+                    .unwrap()
+                    .len(),
+                2
+            );
+        }
+
+        #[test]
+        fn parse_board() {
+            let mut state = "max_turns:\t3\nplayer_point:\t4\nturn:\t0\nscore:\t0\n12@\n23.\n1..\n"
+                .parse::<GameState>()
+                .unwrap();
+
+            assert_eq!((state.h(), state.w(), state.max_turns()), (3, 3, 3));
+            assert_eq!(state.player(), (0, 2));
+            assert_eq!(state.point(1, 0), 2);
+            assert_eq!(state.point(0, 2), 4);
+
+            state.advance(Action::Left);
+            state.advance(Action::Down);
+            assert_eq!(state.score, 5);
+            assert_eq!(
+                format!("{:?}", state),
+                "turn:\t2\nscore:\t5\n1.4\n2@.\n1..\n"
+            );
+
+            let text = format!(
+                "max_turns:\t{}\nplayer_point:\t0\n{:?}",
+                state.max_turns(),
+                state
+            );
+            assert_eq!(
+                format!("{:?}", text.parse::<GameState>().unwrap()),
+                format!("{:?}", state)
+            );
+
+            // The start cell keeps its points until the player comes back
+            let state = GameState::new(3, 4, 5, 7);
+            let (y, x) = state.player();
+            let text = format!(
+                "max_turns:\t5\nplayer_point:\t{}\n{:?}",
+                state.point(y, x),
+                state
+            );
+            assert_eq!(text.parse::<GameState>().unwrap().rows(), state.rows());
+        }
+
+        #[test]
+        fn parse_board_errors() {
+            let header = "max_turns:\t3\nplayer_point:\t0\nturn:\t0\nscore:\t0\n";
+
+            assert!(format!("{}12\n3.\n", header).parse::<GameState>().is_err());
+            assert!(format!("{}1@\n@.\n", header).parse::<GameState>().is_err());
+            assert!(format!("{}1@\n3\n", header).parse::<GameState>().is_err());
+            assert!(format!("{}1@\n3x\n", header).parse::<GameState>().is_err());
+            assert!("turn:\t0\nscore:\t0\n1@\n".parse::<GameState>().is_err());
+            assert!("max_turns:\t3\nturn:\t0\nscore:\t0\n1@\n"
+                .parse::<GameState>()
+                .is_err());
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn json_schema() {
+            let state = "max_turns:\t3\nplayer_point:\t0\nturn:\t1\nscore:\t2\n1.@\n23.\n"
+                .parse::<GameState>()
+                .unwrap();
+            let json = serde_json::to_string(&state).unwrap();
+
+            assert_eq!(
+                json,
+                r#"{"max_turns":3,"turn":1,"score":2,"player":{"y":0,"x":2},"points":[[1,0,0],[2,3,0]]}"#
+            );
+            assert_eq!(
+                format!("{:?}", serde_json::from_str::<GameState>(&json).unwrap()),
+                format!("{:?}", state)
+            );
+            assert_eq!(serde_json::to_string(&Action::Left).unwrap(), r#""left""#);
+
+            let json = json.replace(r#""x":2"#, r#""x":3"#);
+            assert!(serde_json::from_str::<GameState>(&json).is_err());
+
+            let (record, _) = GameRecord::play(&mut FirstAction, &GameState::new(3, 3, 4, 0));
+            let json = serde_json::to_string(&record).unwrap();
+            assert_eq!(serde_json::from_str::<GameRecord>(&json).unwrap(), record);
+        }
+    }
+}
+
+// Problem input and output in the format of a contest judge:
+//
+//   H W T
+//   sy sx
+//   p[0][0] ... p[0][W-1]
+//   ...
+//   p[H-1][0] ... p[H-1][W-1]
+//
+// is answered with the actions in order, e.g. `DLLD`. Tokens may be split
+// over lines in any way.
+//
+// In the interactive version the judge sends the same input, then before
+// every turn a line `turn y x score` with the state the player is in, and
+// the agent answers each with one action on its own line. After the last
+// turn the judge sends `end score` and closes the connection.
+pub mod contest {
+    use std::io::{self, BufRead, Write};
+    use std::str::FromStr;
+
+    use super::maze::{Action, GameState, ParseError, ReplayError};
+    use super::{Agent, GameStateBase};
+
+    pub fn read_input(input: &str) -> Result<GameState, ParseError> {
+        let mut tokens = input.split_whitespace();
+        let mut next = |name: &str| next_token::<usize>(&mut tokens, name);
+
+        let (h, w, max_turns) = (next("H")?, next("W")?, next("T")?);
+        let start = (next("sy")?, next("sx")?);
+        if h == 0 || w == 0 {
+            return Err(ParseError("the board must not be empty".to_string()));
+        }
+        if h == 1 && w == 1 {
+            return Err(ParseError("the board has no cell to move to".to_string()));
+        }
+        if start.0 >= h || start.1 >= w {
+            return Err(ParseError("the start is outside the board".to_string()));
+        }
+
+        let rows = (0..h)
+            .map(|_| {
+                (0..w)
+                    .map(|_| next_token::<i32>(&mut tokens, "a point"))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(token) = tokens.next() {
+            return Err(ParseError(format!(
+                "unexpected {:?} after the board",
+                token
+            )));
+        }
+
+        let max_turns = i32::try_from(max_turns)
+            .map_err(|_| ParseError(format!("T {} is too large", max_turns)))?;
+
+        Ok(GameState::from_board(&rows, start, max_turns))
+    }
+
+    // The input of a game that has not started yet
+    pub fn write_input(state: &GameState) -> String {
+        let (sy, sx) = state.player();
+        let mut input = format!(
+            "{} {} {}\n{} {}\n",
+            state.h(),
+            state.w(),
+            state.max_turns(),
+            sy,
+            sx
+        );
+
+        for row in state.rows() {
+            let row = row.iter().map(i32::to_string).collect::<Vec<_>>();
+            input.push_str(&row.join(" "));
+            input.push('\n');
+        }
+
+        input
+    }
+
+    // Actions are single letters, with or without whitespace between them
+    pub fn read_output(output: &str) -> Result<Vec<Action>, ParseError> {
+        output
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_string().parse())
+            .collect()
+    }
+
+    pub fn write_output(actions: &[Action]) -> String {
+        let mut output = actions.iter().map(Action::to_string).collect::<String>();
+        output.push('\n');
+
+        output
+    }
+
+    // Plays the game with the agent and returns the answer to print
+    pub fn solve<A>(agent: &mut A, state: &GameState) -> Vec<Action>
+    where
+        A: Agent<GameState, Action> + ?Sized,
+    {
+        let mut state = state.clone();
+        let mut actions = Vec::new();
+
+        while !state.is_game_over() {
+            let action = agent.choose_action(&state).unwrap();
+            state.advance(action);
+            actions.push(action);
+        }
+
+        actions
+    }
+
+    // Judges an answer the way the contest would. Stopping before the last
+    // turn is allowed and scores the points collected so far.
+    pub fn score(state: &GameState, actions: &[Action]) -> Result<i32, ReplayError> {
+        let mut state = state.clone();
+
+        for &action in actions {
+            let turn = state.turn() + 1;
+
+            if state.is_game_over() {
+                return Err(ReplayError::GameOver { turn });
+            }
+            if !state.legal_actions().contains(action) {
+                return Err(ReplayError::InvalidAction { turn, action });
+            }
+
+            state.advance(action);
+        }
+
+        Ok(state.score)
+    }
+
+    pub fn write_turn(state: &GameState) -> String {
+        let (y, x) = state.player();
+
+        format!("turn {} {} {} {}\n", state.turn(), y, x, state.score)
+    }
+
+    // Plays as the agent side of the interactive protocol. Returns the final
+    // state once the judge ends the game.
+    pub fn interact<A, R, W>(agent: &mut A, mut input: R, mut output: W) -> io::Result<GameState>
+    where
+        A: Agent<GameState, Action> + ?Sized,
+        R: BufRead,
+        W: Write,
+    {
+        let mut read_line = || {
+            let mut line = String::new();
+            match input.read_line(&mut line)? {
+                0 => Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "the judge closed the connection",
+                )),
+                _ => Ok(line),
+            }
+        };
+
+        let mut problem = read_line()?;
+        let h = next_token::<usize>(&mut problem.split_whitespace(), "H").map_err(invalid_data)?;
+        for _ in 0..=h {
+            problem.push_str(&read_line()?);
+        }
+        let mut state = read_input(&problem).map_err(invalid_data)?;
+
+        loop {
+            let line = read_line()?;
+            if line.starts_with("end") {
+                return Ok(state);
+            }
+
+            // A judge may end its lines with \r\n
+            if line.trim_end() != write_turn(&state).trim_end() {
+                return Err(invalid_data(format!(
+                    "expected {:?} from the judge, got {:?}",
+                    write_turn(&state).trim_end(),
+                    line.trim_end()
+                )));
+            }
+
+            let action = agent
+                .choose_action(&state)
+                .ok_or_else(|| invalid_data("the agent has no action"))?;
+            writeln!(output, "{}", action)?;
+            output.flush()?;
+
+            state.advance(action);
+        }
+    }
+
+    fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(err: E) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+
+    fn next_token<'a, T: FromStr>(
+        tokens: &mut impl Iterator<Item = &'a str>,
+        name: &str,
+    ) -> Result<T, ParseError> {
+        let token = tokens
+            .next()
+            .ok_or_else(|| ParseError(format!("missing {}", name)))?;
+
+        token
+            .parse()
+            .map_err(|_| ParseError(format!("cannot parse {} {:?}", name, token)))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{
+            interact, read_input, read_output, score, solve, write_input, write_output, write_turn,
+        };
+        use crate::maze::{Action, GameState, ReplayError};
+        use crate::{Agent, GameStateBase};
+
+        struct FirstAction;
+
+        impl Agent<GameState, Action> for FirstAction {
+            fn choose_action(&mut self, state: &GameState) -> Option<Action> {
+                state.legal_actions().next()
+            }
+        }
+
+        #[test]
+        fn input_and_output() {
+            let input = "3 3 4\n0 2\n1 2 3\n4 5 6\n7 8 9\n";
+            let state = read_input(input).unwrap();
+
+            assert_eq!((state.h(), state.w(), state.max_turns()), (3, 3, 4));
+            assert_eq!(state.player(), (0, 2));
+            assert_eq!(state.point(2, 0), 7);
+            assert_eq!(write_input(&state), input);
+
+            let actions = read_output("DL\nLD\n").unwrap();
+            assert_eq!(write_output(&actions), "DLLD\n");
+            assert_eq!(score(&state, &actions), Ok(6 + 5 + 4 + 7));
+            assert_eq!(score(&state, &actions[..1]), Ok(6));
+
+            let actions = solve(&mut FirstAction, &state);
+            assert_eq!(actions.len(), 4);
+            assert_eq!(
+                score(&state, &actions),
+                Ok(FirstAction.play_game(&state).score)
+            );
+        }
+
+        #[test]
+        fn rejected_answers() {
+            let state = read_input("2 2 2 0 0 1 2 3 4").unwrap();
+
+            // The start cell is only collected when the player comes back
+            assert_eq!(score(&state, &read_output("DU").unwrap()), Ok(3 + 1));
+            assert_eq!(
+                score(&state, &[Action::Up]),
+                Err(ReplayError::InvalidAction {
+                    turn: 1,
+                    action: Action::Up
+                })
+            );
+            assert_eq!(
+                score(&state, &[Action::Down, Action::Up, Action::Down]),
+                Err(ReplayError::GameOver { turn: 3 })
+            );
+            assert!(read_output("DX").is_err());
+            assert!(read_input("2 2 2 0 2 1 2 3 4").is_err());
+            assert!(read_input("2 2 2 0 0 1 2 3").is_err());
+            assert!(read_input("2 2 2 0 0 1 2 3 4 5").is_err());
+            assert!(read_input("1 1 2 0 0 1").is_err());
+        }
+
+        #[test]
+        fn interactive_protocol() {
+            let state = GameState::new(4, 3, 5, 2);
+            let mut judge = write_input(&state);
+            let mut expected = String::new();
+
+            let mut next_state = state.clone();
+            for action in solve(&mut FirstAction, &state) {
+                judge.push_str(&write_turn(&next_state));
+                expected.push_str(&format!("{}\n", action));
+                next_state.advance(action);
+            }
+            judge.push_str(&format!("end {}\n", next_state.score));
+
+            let mut output = Vec::new();
+            let last_state = interact(&mut FirstAction, judge.as_bytes(), &mut output).unwrap();
+            assert_eq!(String::from_utf8(output).unwrap(), expected);
+            assert_eq!(last_state.score, next_state.score);
+
+            let crlf = judge.replace('\n', "\r\n");
+            let mut output = Vec::new();
+            let last_state = interact(&mut FirstAction, crlf.as_bytes(), &mut output).unwrap();
+            assert_eq!(String::from_utf8(output).unwrap(), expected);
+            assert_eq!(last_state.score, next_state.score);
+
+            let wrong = judge.replacen("turn 1", "turn 2", 1);
+            assert!(interact(&mut FirstAction, wrong.as_bytes(), Vec::new()).is_err());
+            let cut = &judge[..judge.rfind("end").unwrap()];
+            assert!(interact(&mut FirstAction, cut.as_bytes(), Vec::new()).is_err());
+        }
+    }
+}
+
+// Counters filled in by the search agents. They only record anything when
+// the crate is built with the `stats` feature; otherwise every method is
+// empty and compiles away.
+pub mod stats {
+    use std::fmt::{Display, Formatter, Result};
+    use std::time::Duration;
+    #[cfg(feature = "stats")]
+    use std::time::Instant;
+
+    pub const ENABLED: bool = cfg!(feature = "stats");
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct SearchStats {
+        // States created by advancing another one
+        pub nodes_generated: u64,
+        // States whose actions were tried
+        pub nodes_expanded: u64,
+        // Deepest layer a generated state was in, the current state being 0
+        pub max_depth: usize,
+        // Nodes expanded at each depth, summed over all turns
+        pub beam_occupancy: Vec<u64>,
+        // Time spent in each call to choose_action
+        pub turn_times: Vec<Duration>,
+    }
+
+    // Started at the beginning of a turn and handed back at its end
+    pub struct TurnTimer {
+        #[cfg(feature = "stats")]
+        started: Instant,
+    }
+
+    impl SearchStats {
+        #[inline]
+        pub fn start_turn(&self) -> TurnTimer {
+            TurnTimer {
+                #[cfg(feature = "stats")]
+                started: Instant::now(),
+            }
+        }
+
+        #[inline]
+        pub fn end_turn(&mut self, _timer: TurnTimer) {
+            #[cfg(feature = "stats")]
+            self.turn_times.push(_timer.started.elapsed());
+        }
+
+        // A state was expanded `depth` actions away from the current one
+        #[inline]
+        pub fn expand(&mut self, _depth: usize) {
+            #[cfg(feature = "stats")]
+            {
+                self.nodes_expanded += 1;
+                if self.beam_occupancy.len() <= _depth {
+                    self.beam_occupancy.resize(_depth + 1, 0);
+                }
+                self.beam_occupancy[_depth] += 1;
+            }
+        }
+
+        // A state was generated `depth` actions away from the current one
+        #[inline]
+        pub fn generate(&mut self, _depth: usize) {
+            #[cfg(feature = "stats")]
+            {
+                self.nodes_generated += 1;
+                self.max_depth = self.max_depth.max(_depth);
+            }
+        }
+
+        pub fn total_time(&self) -> Duration {
+            self.turn_times.iter().sum()
+        }
+
+        // 0 when no time was recorded, as without the `stats` feature
+        pub fn nodes_per_second(&self) -> f64 {
+            let secs = self.total_time().as_secs_f64();
+            if secs == 0.0 {
+                return 0.0;
+            }
+
+            self.nodes_generated as f64 / secs
+        }
+    }
+
+    // Per-turn figures are means over the turns played
+    impl Display for SearchStats {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            let turns = self.turn_times.len().max(1) as f64;
+            let occupancy = self
+                .beam_occupancy
+                .iter()
+                .map(|&nodes| format!("{:.1}", nodes as f64 / turns))
+                .collect::<Vec<_>>();
+            let max_turn_time = self.turn_times.iter().max().copied().unwrap_or_default();
+
+            writeln!(f, "nodes_generated:\t{}", self.nodes_generated)?;
+            writeln!(f, "nodes_expanded:\t{}", self.nodes_expanded)?;
+            writeln!(f, "max_depth:\t{}", self.max_depth)?;
+            writeln!(f, "beam_occupancy:\t{}", occupancy.join(" "))?;
+            writeln!(
+                f,
+                "time_per_turn:\t{:.3} ms mean, {:.3} ms max",
+                self.total_time().as_secs_f64() * 1000.0 / turns,
+                max_turn_time.as_secs_f64() * 1000.0
+            )?;
+            writeln!(f, "nodes_per_second:\t{:.0}", self.nodes_per_second())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::SearchStats;
+
+        #[cfg(feature = "stats")]
+        #[test]
+        fn counts() {
+            let mut stats = SearchStats::default();
+            let timer = stats.start_turn();
+            stats.expand(0);
+            stats.generate(1);
+            stats.generate(1);
+            stats.expand(1);
+            stats.generate(2);
+            stats.end_turn(timer);
+
+            assert_eq!((stats.nodes_generated, stats.nodes_expanded), (3, 2));
+            assert_eq!(stats.max_depth, 2);
+            assert_eq!(stats.beam_occupancy, vec![1, 1]);
+            assert_eq!(stats.turn_times.len(), 1);
+            assert!(stats.to_string().contains("beam_occupancy:\t1.0 1.0\n"));
+        }
+
+        #[test]
+        fn no_time_recorded() {
+            let stats = SearchStats {
+                nodes_generated: 10,
+                ..Default::default()
+            };
+
+            assert_eq!(stats.nodes_per_second(), 0.0);
+            assert!(stats.to_string().contains("nodes_per_second:\t0\n"));
+        }
+    }
+}
+
+// The nodes a search agent explored during its last turn, written out as a
+// Graphviz graph. Only the first `max_nodes` nodes are kept; children of a
+// dropped node are dropped too, so the graph stays connected.
+pub mod search_tree {
+    use std::fmt::{Display, Write};
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Node<S> {
+        pub parent: Option<usize>,
+        // None for the root
+        pub action: Option<S>,
+        pub depth: usize,
+        pub score: i32,
+        // Whether the node made it into a beam instead of being cut
+        pub survived: bool,
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct SearchTree<S> {
+        max_nodes: usize,
+        nodes: Vec<Node<S>>,
+        dropped: usize,
+    }
+
+    // A tree with no room for nodes, which agents keep when not recording
+    impl<S> Default for SearchTree<S> {
+        fn default() -> Self {
+            SearchTree::new(0)
+        }
+    }
+
+    impl<S> SearchTree<S> {
+        pub fn new(max_nodes: usize) -> Self {
+            SearchTree {
+                max_nodes,
+                nodes: Vec::new(),
+                dropped: 0,
+            }
+        }
+
+        pub fn is_recording(&self) -> bool {
+            self.max_nodes > 0
+        }
+
+        pub fn nodes(&self) -> &[Node<S>] {
+            &self.nodes
+        }
+
+        // Nodes left out because the tree was full
+        pub fn dropped(&self) -> usize {
+            self.dropped
+        }
+
+        // Starts a new turn's tree from the current state, which survives
+        pub fn start(&mut self, score: i32) -> Option<usize> {
+            self.nodes.clear();
+            let root = self.push(Node {
+                parent: None,
+                action: None,
+                depth: 0,
+                score,
+                survived: true,
+            });
+            self.dropped = 0;
+
+            root
+        }
+
+        // Adds a child of `parent`, returning its id unless it was dropped
+        pub fn add(&mut self, parent: Option<usize>, action: S, score: i32) -> Option<usize> {
+            let Some(parent) = parent else {
+                self.dropped += self.is_recording() as usize;
+                return None;
+            };
+
+            self.push(Node {
+                parent: Some(parent),
+                action: Some(action),
+                depth: self.nodes[parent].depth + 1,
+                score,
+                survived: false,
+            })
+        }
+
+        pub fn survive(&mut self, node: Option<usize>) {
+            if let Some(node) = node {
+                self.nodes[node].survived = true;
+            }
+        }
+
+        fn push(&mut self, node: Node<S>) -> Option<usize> {
+            if self.nodes.len() == self.max_nodes {
+                self.dropped += 1;
+                return None;
+            }
+
+            self.nodes.push(node);
+            Some(self.nodes.len() - 1)
+        }
+    }
+
+    impl<S: Display> SearchTree<S> {
+        // Surviving nodes are filled, cut ones dashed
+        pub fn to_dot(&self) -> String {
+            let mut dot = String::from("digraph search {\n    node [shape=box];\n");
+
+            for (id, node) in self.nodes.iter().enumerate() {
+                let action = match &node.action {
+                    Some(action) => action.to_string(),
+                    None => "root".to_string(),
+                };
+                let style = if node.survived {
+                    "style=filled, fillcolor=lightblue"
+                } else {
+                    "style=dashed"
+                };
+                writeln!(
+                    dot,
+                    "    n{} [label=\"{}\\nscore {}\\ndepth {}\", {}];",
+                    id, action, node.score, node.depth, style
+                )
+                .unwrap();
+                if let Some(parent) = node.parent {
+                    writeln!(dot, "    n{} -> n{};", parent, id).unwrap();
+                }
+            }
+            if self.dropped > 0 {
+                writeln!(
+                    dot,
+                    "    dropped [label=\"{} more nodes\", shape=plaintext];",
+                    self.dropped
+                )
+                .unwrap();
+            }
+            dot.push_str("}\n");
+
+            dot
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::SearchTree;
+
+        #[test]
+        fn limits_nodes() {
+            let mut tree = SearchTree::new(3);
+            let root = tree.start(0);
+            let a = tree.add(root, 'a', 1);
+            let b = tree.add(root, 'b', 2);
+            let c = tree.add(a, 'c', 3);
+            let d = tree.add(c, 'd', 4);
+            tree.survive(b);
+
+            assert_eq!((root, a, b, c, d), (Some(0), Some(1), Some(2), None, None));
+            assert_eq!(tree.nodes()[2].depth, 1);
+            assert_eq!(tree.dropped(), 2);
+            assert_eq!(
+                tree.to_dot(),
+                "digraph search {\n    node [shape=box];\n\
+                 \x20   n0 [label=\"root\\nscore 0\\ndepth 0\", style=filled, fillcolor=lightblue];\n\
+                 \x20   n1 [label=\"a\\nscore 1\\ndepth 1\", style=dashed];\n\
+                 \x20   n0 -> n1;\n\
+                 \x20   n2 [label=\"b\\nscore 2\\ndepth 1\", style=filled, fillcolor=lightblue];\n\
+                 \x20   n0 -> n2;\n\
+                 \x20   dropped [label=\"2 more nodes\", shape=plaintext];\n\
+                 }\n"
+            );
+        }
+
+        #[test]
+        fn records_nothing_by_default() {
+            let mut tree = SearchTree::default();
+            let root = tree.start(0);
+            assert_eq!(tree.add(root, 'a', 1), None);
+            assert!(tree.nodes().is_empty());
+            assert_eq!(tree.dropped(), 0);
+        }
+    }
 }
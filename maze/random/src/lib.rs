@@ -0,0 +1,24 @@
+use lib::{
+    maze::{Action, GameState},
+    Agent,
+};
+use rng::Pcg32;
+
+pub struct RandomAgent {
+    rng: Pcg32,
+}
+
+impl RandomAgent {
+    pub fn new(seed: u64) -> Self {
+        RandomAgent {
+            rng: Pcg32::new(seed),
+        }
+    }
+}
+
+impl Agent<GameState, Action> for RandomAgent {
+    fn choose_action(&mut self, state: &GameState) -> Option<Action> {
+        let mut actions = state.legal_actions();
+        actions.nth(self.rng.gen_range(0..actions.len()))
+    }
+}
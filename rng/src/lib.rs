@@ -0,0 +1,186 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::ops::Range;
+use std::{env, process};
+
+const MULTIPLIER: u64 = 6364136223846793005;
+const INCREMENT: u64 = 1442695040888963407;
+
+// PCG32 (XSH-RR), the generator behind every board and agent seed. The stream
+// is part of the meaning of a seed and must never change:
+//
+//   new(seed):    state = 0, step, state += seed, step
+//   step:         state = state * 6364136223846793005 + 1442695040888963407
+//   next_u32:     old = state, step, then ((old ^ (old >> 18)) >> 27) as u32
+//                 rotated right by (old >> 59)
+//   next_u64:     next_u32 << 32 | next_u32, the first draw in the high half
+//   gen_range:    the high 64 bits of next_u64 * len, drawing again while the
+//                 low 64 bits are below 2^64 mod len (Lemire's method)
+//   gen_f64:      (next_u64 >> 11) / 2^53
+//   gen_bool(p):  gen_f64 < p
+//
+// This is the reference pcg32 seeded with pcg32_srandom_r(seed, 0x0a02bdbf7bb3c0a7).
+#[derive(Clone, Debug)]
+pub struct Pcg32 {
+    state: u64,
+}
+
+impl Pcg32 {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Pcg32 { state: 0 };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+
+        rng
+    }
+
+    fn step(&mut self) {
+        self.state = self.state.wrapping_mul(MULTIPLIER).wrapping_add(INCREMENT);
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+
+        let xorshifted = ((old ^ (old >> 18)) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let high = self.next_u32() as u64;
+
+        (high << 32) | self.next_u32() as u64
+    }
+
+    // Uniform in the range, which must not be empty
+    pub fn gen_range(&mut self, range: Range<usize>) -> usize {
+        assert!(range.start < range.end, "empty range {:?}", range);
+        let len = (range.end - range.start) as u64;
+        let threshold = len.wrapping_neg() % len;
+
+        loop {
+            let product = self.next_u64() as u128 * len as u128;
+            if product as u64 >= threshold {
+                return range.start + (product >> 64) as usize;
+            }
+        }
+    }
+
+    // Uniform in [0, 1)
+    pub fn gen_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn gen_bool(&mut self, p: f64) -> bool {
+        self.gen_f64() < p
+    }
+}
+
+// A different seed on every call, for runs where none is given
+pub fn random_seed() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+// A game's seed is split the same way everywhere: the board is built from
+// the first draw of Pcg32::new(seed) and the agent is seeded with the second,
+// whether or not it uses randomness. Repetition n of a game seeds its agent
+// with draw n + 2 instead, so repeated games keep their board.
+pub fn board_seed(seed: u64) -> u64 {
+    Pcg32::new(seed).next_u64()
+}
+
+pub fn agent_seed(seed: u64, repetition: u32) -> u64 {
+    let mut rng = Pcg32::new(seed);
+    rng.next_u64();
+    let mut agent_seed = rng.next_u64();
+    for _ in 0..repetition {
+        agent_seed = rng.next_u64();
+    }
+
+    agent_seed
+}
+
+// The value after `--seed` on the command line, or a random seed when the
+// flag is missing. Exits with an error when the value is not a u64.
+pub fn seed_from_args() -> u64 {
+    match parse_seed(env::args()) {
+        Ok(seed) => seed.unwrap_or_else(random_seed),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn parse_seed<I: Iterator<Item = String>>(args: I) -> Result<Option<u64>, String> {
+    let mut args = args.skip_while(|arg| arg != "--seed");
+    if args.next().is_none() {
+        return Ok(None);
+    }
+
+    match args.next() {
+        Some(seed) => seed
+            .parse()
+            .map(Some)
+            .map_err(|err| format!("invalid value '{}' for --seed: {}", seed, err)),
+        None => Err("--seed needs a value".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{agent_seed, board_seed, parse_seed, Pcg32};
+
+    // Seeds keep their meaning only as long as these values hold
+    #[test]
+    fn fixed_stream() {
+        let mut rng = Pcg32::new(42);
+        let values = (0..4).map(|_| rng.next_u32()).collect::<Vec<_>>();
+        assert_eq!(values, vec![3270867926, 1795671209, 1924641435, 1143034755]);
+
+        let mut rng = Pcg32::new(0);
+        assert_eq!(rng.next_u64(), 16727391898930432985);
+        assert_eq!(
+            (0..8).map(|_| rng.gen_range(1..10)).collect::<Vec<_>>(),
+            vec![5, 8, 1, 8, 9, 9, 9, 4]
+        );
+
+        let mut rng = Pcg32::new(3);
+        let draws = (0..3).map(|_| rng.next_u64()).collect::<Vec<_>>();
+        assert_eq!(board_seed(3), draws[0]);
+        assert_eq!((agent_seed(3, 0), agent_seed(3, 1)), (draws[1], draws[2]));
+    }
+
+    #[test]
+    fn ranges() {
+        let mut rng = Pcg32::new(7);
+        let mut counts = [0; 3];
+
+        for _ in 0..3000 {
+            counts[rng.gen_range(2..5) - 2] += 1;
+            let x = rng.gen_f64();
+            assert!((0.0..1.0).contains(&x));
+        }
+
+        assert!(counts.iter().all(|&count| (900..1100).contains(&count)));
+        assert!(!rng.gen_bool(0.0));
+        assert!(rng.gen_bool(1.0));
+    }
+
+    #[test]
+    fn seed_arguments() {
+        let parse = |args: &[&str]| parse_seed(args.iter().map(|arg| arg.to_string()));
+
+        assert_eq!(parse(&["agent"]), Ok(None));
+        assert_eq!(parse(&["agent", "--seed", "42"]), Ok(Some(42)));
+        assert_eq!(
+            parse(&["agent", "--seed", "x"]),
+            Err("invalid value 'x' for --seed: invalid digit found in string".to_string())
+        );
+        assert_eq!(
+            parse(&["agent", "--seed"]),
+            Err("--seed needs a value".to_string())
+        );
+    }
+}